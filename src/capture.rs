@@ -0,0 +1,520 @@
+//! Screenshots, and GIF clips of the last seconds of play from a rolling buffer
+//! of downscaled frames.
+
+use crate::{GameState, game::GamePhase, stats::data_dir};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::TextureFormat,
+        view::screenshot::{Screenshot, ScreenshotCaptured, save_to_disk},
+    },
+};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::PathBuf,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const CLIP_FRAME_INTERVAL: f32 = 0.1; // seconds between buffered frames
+const CLIP_LENGTH: f32 = 10.0; // seconds kept in the rolling buffer
+const CLIP_MAX_WIDTH: u32 = 320; // buffered frames are downscaled to at most this width
+
+const SCREENSHOT_KEY: KeyCode = KeyCode::F12;
+const SAVE_CLIP_KEY: KeyCode = KeyCode::F8;
+
+/// Downscaled frame, already mapped to the clip palette.
+#[derive(Clone)]
+struct ClipFrame {
+    width: u16,
+    height: u16,
+    indices: Vec<u8>,
+}
+
+/// Rolling buffer with the last seconds of play.
+#[derive(Resource)]
+struct ClipBuffer {
+    frames: VecDeque<ClipFrame>,
+    timer: Timer,
+}
+
+impl Default for ClipBuffer {
+    fn default() -> Self {
+        ClipBuffer {
+            frames: VecDeque::new(),
+            timer: Timer::from_seconds(CLIP_FRAME_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<ClipBuffer>()
+        .add_systems(OnEnter(GameState::Game), clear_clip_buffer)
+        .add_systems(
+            Update,
+            (
+                screenshot_system,
+                save_clip_system,
+                clip_capture_system.run_if(in_state(GamePhase::Running)),
+            ),
+        );
+}
+
+fn screenshot_dir() -> PathBuf {
+    data_dir().join("screenshots")
+}
+
+fn timestamp() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default()
+}
+
+fn screenshot_system(mut commands: Commands, keyboard_input: Res<ButtonInput<KeyCode>>) {
+    if !keyboard_input.just_pressed(SCREENSHOT_KEY) {
+        return;
+    }
+
+    let dir = screenshot_dir();
+    if let Err(error) = fs::create_dir_all(&dir) {
+        warn!("Could not create screenshot directory: {}", error);
+        return;
+    }
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(
+            dir.join(format!("screenshot-{}.png", timestamp())),
+        ));
+}
+
+/// Clips only ever show the current run.
+fn clear_clip_buffer(mut clip_buffer: ResMut<ClipBuffer>) {
+    *clip_buffer = ClipBuffer::default();
+}
+
+fn clip_capture_system(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut clip_buffer: ResMut<ClipBuffer>,
+) {
+    clip_buffer.timer.tick(time.delta());
+    if !clip_buffer.timer.just_finished() {
+        return;
+    }
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(buffer_clip_frame);
+}
+
+fn buffer_clip_frame(captured: On<ScreenshotCaptured>, mut clip_buffer: ResMut<ClipBuffer>) {
+    let image = &captured.image;
+
+    // Sampled straight from the window's surface format when possible, so the
+    // full-size frame is never copied
+    let frame = match (image.texture_descriptor.format, image.data.as_deref()) {
+        (TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb, Some(data)) => {
+            downscale(data, image.width(), image.height(), [0, 1, 2])
+        }
+        (TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb, Some(data)) => {
+            downscale(data, image.width(), image.height(), [2, 1, 0])
+        }
+        _ => {
+            let Ok(image) = image.clone().try_into_dynamic() else {
+                return;
+            };
+            let image = image.to_rgba8();
+            downscale(image.as_raw(), image.width(), image.height(), [0, 1, 2])
+        }
+    };
+
+    clip_buffer.frames.push_back(frame);
+
+    let max_frames = (CLIP_LENGTH / CLIP_FRAME_INTERVAL) as usize;
+    while clip_buffer.frames.len() > max_frames {
+        clip_buffer.frames.pop_front();
+    }
+}
+
+fn save_clip_system(keyboard_input: Res<ButtonInput<KeyCode>>, clip_buffer: Res<ClipBuffer>) {
+    if !keyboard_input.just_pressed(SAVE_CLIP_KEY) || clip_buffer.frames.is_empty() {
+        return;
+    }
+
+    let frames: Vec<ClipFrame> = clip_buffer.frames.iter().cloned().collect();
+    let path = screenshot_dir().join(format!("clip-{}.gif", timestamp()));
+
+    // Encoding a full clip takes long enough to hitch a frame
+    thread::spawn(move || {
+        let gif = encode_gif(&frames, (CLIP_FRAME_INTERVAL * 100.0) as u16);
+        match fs::create_dir_all(screenshot_dir()).and_then(|_| fs::write(&path, gif)) {
+            Ok(()) => info!("Clip saved to {}", path.display()),
+            Err(error) => warn!("Could not save clip: {}", error),
+        }
+    });
+}
+
+/// Nearest-neighbour downscale of a 4-byte-per-pixel image into clip palette
+/// indices, reading red, green and blue at the `channels` offsets of each pixel.
+fn downscale(pixels: &[u8], width: u32, height: u32, channels: [usize; 3]) -> ClipFrame {
+    let step = width.div_ceil(CLIP_MAX_WIDTH).max(1);
+    let scaled_width = width / step;
+    let scaled_height = height / step;
+
+    let mut indices = Vec::with_capacity((scaled_width * scaled_height) as usize);
+    for y in 0..scaled_height {
+        for x in 0..scaled_width {
+            let i = (((y * step) * width + x * step) * 4) as usize;
+            indices.push(palette_index(
+                pixels[i + channels[0]],
+                pixels[i + channels[1]],
+                pixels[i + channels[2]],
+            ));
+        }
+    }
+
+    ClipFrame {
+        width: scaled_width as u16,
+        height: scaled_height as u16,
+        indices,
+    }
+}
+
+/// Index in the 6x6x6 color cube used as the clip palette.
+fn palette_index(r: u8, g: u8, b: u8) -> u8 {
+    let level = |channel: u8| ((channel as u16 * 5 + 127) / 255) as u8;
+    level(r) * 36 + level(g) * 6 + level(b)
+}
+
+/// Looping GIF of `frames`, each shown for `delay` hundredths of a second.
+fn encode_gif(frames: &[ClipFrame], delay: u16) -> Vec<u8> {
+    let (width, height) = frames
+        .last()
+        .map_or((0, 0), |frame| (frame.width, frame.height));
+
+    let mut gif = Vec::new();
+    gif.extend_from_slice(b"GIF89a");
+    gif.extend_from_slice(&width.to_le_bytes());
+    gif.extend_from_slice(&height.to_le_bytes());
+    gif.extend_from_slice(&[0xF7, 0, 0]); // 256 entry global color table
+
+    for index in 0..=255u16 {
+        let (r, g, b) = if index < 216 {
+            (index / 36, index / 6 % 6, index % 6)
+        } else {
+            (0, 0, 0)
+        };
+        gif.extend_from_slice(&[(r * 51) as u8, (g * 51) as u8, (b * 51) as u8]);
+    }
+
+    // Loop forever
+    gif.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+    gif.extend_from_slice(b"NETSCAPE2.0");
+    gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for frame in frames {
+        // Frames captured before a window resize are dropped, the clip keeps the
+        // size the window has now
+        if frame.width != width || frame.height != height {
+            continue;
+        }
+
+        gif.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        gif.extend_from_slice(&delay.to_le_bytes());
+        gif.extend_from_slice(&[0x00, 0x00]);
+
+        gif.push(0x2C);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&width.to_le_bytes());
+        gif.extend_from_slice(&height.to_le_bytes());
+        gif.push(0x00);
+
+        gif.push(8); // minimum code size
+        for block in lzw_encode(&frame.indices).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend_from_slice(block);
+        }
+        gif.push(0x00);
+    }
+
+    gif.push(0x3B);
+    gif
+}
+
+/// Variable-width LZW compression of 8-bit indices, as GIF image data expects.
+fn lzw_encode(indices: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END: u16 = 257;
+    const MAX_CODE: u16 = 4095;
+
+    let mut output = Vec::new();
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    let mut write = |code: u16, width: u32, output: &mut Vec<u8>| {
+        bits |= (code as u32) << bit_count;
+        bit_count += width;
+        while bit_count >= 8 {
+            output.push(bits as u8);
+            bits >>= 8;
+            bit_count -= 8;
+        }
+    };
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut width = 9;
+    let mut highest = END;
+
+    write(CLEAR, width, &mut output);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        write(END, width, &mut output);
+        if bit_count > 0 {
+            output.push(bits as u8);
+        }
+        return output;
+    };
+
+    let mut code = first as u16;
+    for &index in rest {
+        if let Some(&existing) = table.get(&(code, index)) {
+            code = existing;
+            continue;
+        }
+
+        write(code, width, &mut output);
+
+        highest += 1;
+        if highest == 1 << width {
+            width += 1;
+        }
+        if highest == MAX_CODE {
+            write(CLEAR, width, &mut output);
+            table.clear();
+            width = 9;
+            highest = END;
+        } else {
+            table.insert((code, index), highest);
+        }
+
+        code = index as u16;
+    }
+
+    write(code, width, &mut output);
+    highest += 1;
+    if highest == 1 << width && width < 12 {
+        width += 1;
+    }
+    write(END, width, &mut output);
+
+    if bit_count > 0 {
+        output.push(bits as u8);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference GIF LZW decoder: decoded indices, clear codes seen, and the
+    /// widest code read.
+    fn lzw_decode(data: &[u8]) -> (Vec<u8>, usize, u32) {
+        const CLEAR: usize = 256;
+        const END: usize = 257;
+
+        let mut reader_bits: u64 = 0;
+        let mut reader_count = 0;
+        let mut bytes = data.iter();
+        let mut read = |width: u32| {
+            while reader_count < width {
+                reader_bits |= (*bytes.next()? as u64) << reader_count;
+                reader_count += 8;
+            }
+            let code = (reader_bits & ((1 << width) - 1)) as usize;
+            reader_bits >>= width;
+            reader_count -= width;
+            Some(code)
+        };
+
+        let reset = || -> Vec<Vec<u8>> {
+            let mut table: Vec<Vec<u8>> = (0..=255).map(|index| vec![index]).collect();
+            table.extend([Vec::new(), Vec::new()]);
+            table
+        };
+
+        let mut output = Vec::new();
+        let mut table = reset();
+        let mut width = 9;
+        let mut widest = width;
+        let mut clears = 0;
+        let mut previous: Option<usize> = None;
+
+        while let Some(code) = read(width) {
+            widest = widest.max(width);
+            match code {
+                CLEAR => {
+                    table = reset();
+                    width = 9;
+                    clears += 1;
+                    previous = None;
+                    continue;
+                }
+                END => break,
+                _ => {}
+            }
+
+            let entry = match previous {
+                None => table[code].clone(),
+                Some(previous) => {
+                    let entry = if code < table.len() {
+                        table[code].clone()
+                    } else {
+                        let mut entry = table[previous].clone();
+                        entry.push(table[previous][0]);
+                        entry
+                    };
+                    let mut new_entry = table[previous].clone();
+                    new_entry.push(entry[0]);
+                    table.push(new_entry);
+                    if table.len() == 1 << width && width < 12 {
+                        width += 1;
+                    }
+                    entry
+                }
+            };
+
+            output.extend_from_slice(&entry);
+            previous = Some(code);
+        }
+
+        (output, clears, widest)
+    }
+
+    /// Deterministic noisy indices, so the dictionary keeps growing.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state: u32 = 0x1234_5678;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state % 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lzw_round_trips_a_small_frame() {
+        let indices: Vec<u8> = (0..8 * 6).map(|i| (i % 5 * 43) as u8).collect();
+
+        let (decoded, clears, widest) = lzw_decode(&lzw_encode(&indices));
+
+        assert_eq!(decoded, indices);
+        assert_eq!(clears, 1);
+        assert_eq!(widest, 9);
+    }
+
+    #[test]
+    fn lzw_round_trips_an_empty_frame() {
+        let (decoded, clears, _) = lzw_decode(&lzw_encode(&[]));
+
+        assert!(decoded.is_empty());
+        assert_eq!(clears, 1);
+    }
+
+    #[test]
+    fn lzw_codes_widen_as_the_table_grows() {
+        // Enough new strings to pass 512 and 1024 entries, but not 4096
+        let indices = noise(2000);
+
+        let (decoded, clears, widest) = lzw_decode(&lzw_encode(&indices));
+
+        assert_eq!(decoded, indices);
+        assert_eq!(clears, 1);
+        assert_eq!(widest, 11);
+    }
+
+    #[test]
+    fn lzw_clears_a_full_table() {
+        let indices = noise(40_000);
+
+        let (decoded, clears, widest) = lzw_decode(&lzw_encode(&indices));
+
+        assert_eq!(decoded, indices);
+        assert!(clears > 1);
+        assert_eq!(widest, 12);
+    }
+
+    #[test]
+    fn lzw_widens_exactly_at_table_boundaries() {
+        // Every length around the 512 entry boundary, where encoder and decoder
+        // have to agree on the exact code the width changes at
+        for len in 400..700 {
+            let indices = noise(len);
+            let (decoded, _, _) = lzw_decode(&lzw_encode(&indices));
+            assert_eq!(decoded, indices, "length {len}");
+        }
+    }
+
+    #[test]
+    fn gif_holds_the_downscaled_frames() {
+        // Blue-green-red pixels, as a BGRA surface hands them out
+        let pixels: Vec<u8> = [255, 0, 0, 255].repeat(CLIP_MAX_WIDTH as usize * 4 * 2);
+        let frame = downscale(&pixels, CLIP_MAX_WIDTH * 2, 4, [2, 1, 0]);
+
+        assert_eq!((frame.width, frame.height), (CLIP_MAX_WIDTH as u16, 2));
+        assert!(
+            frame
+                .indices
+                .iter()
+                .all(|&index| index == palette_index(0, 0, 255))
+        );
+
+        let gif = encode_gif(&[frame.clone(), frame.clone()], 10);
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(u16::from_le_bytes([gif[6], gif[7]]), frame.width);
+        assert_eq!(u16::from_le_bytes([gif[8], gif[9]]), frame.height);
+        assert_eq!(gif.last(), Some(&0x3B));
+
+        // First image descriptor, after the header, color table, loop and
+        // graphic control extensions
+        let image_start = 13 + 256 * 3 + 19 + 8;
+        assert_eq!(gif[image_start], 0x2C);
+
+        let mut data = Vec::new();
+        let mut i = image_start + 11;
+        while gif[i] != 0 {
+            let len = gif[i] as usize;
+            data.extend_from_slice(&gif[i + 1..=i + len]);
+            i += len + 1;
+        }
+        assert_eq!(lzw_decode(&data).0, frame.indices);
+    }
+
+    #[test]
+    fn gif_keeps_the_size_of_the_last_frame() {
+        let small = ClipFrame {
+            width: 2,
+            height: 2,
+            indices: vec![0; 4],
+        };
+        let large = ClipFrame {
+            width: 4,
+            height: 2,
+            indices: vec![1; 8],
+        };
+
+        let gif = encode_gif(&[small.clone(), large.clone(), small, large.clone()], 10);
+
+        assert_eq!(u16::from_le_bytes([gif[6], gif[7]]), 4);
+        assert_eq!(u16::from_le_bytes([gif[8], gif[9]]), 2);
+        assert_eq!(gif, encode_gif(&[large.clone(), large], 10));
+    }
+}
@@ -0,0 +1,104 @@
+//! Writes a crash report to disk when the game panics, and surfaces the last one
+//! on the next launch.
+
+use crate::stats::data_dir;
+
+use bevy::prelude::*;
+
+use std::{
+    backtrace::Backtrace,
+    fs,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const LAST_CRASH_FILE_NAME: &str = "last-crash.txt";
+
+/// Short description of the run in progress, included in crash reports.
+static RUN_SUMMARY: Mutex<Option<String>> = Mutex::new(None);
+
+/// Report written by the previous session's panic, if any.
+#[derive(Resource)]
+pub(crate) struct PreviousCrash(pub(crate) PathBuf);
+
+pub(crate) fn plugin(app: &mut App) {
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        previous_hook(info);
+        match write_report(info) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(error) => eprintln!("Could not write crash report: {}", error),
+        }
+    }));
+
+    if let Some(path) = take_previous_crash() {
+        app.insert_resource(PreviousCrash(path));
+    }
+}
+
+/// Replaces the run summary included in crash reports.
+pub(crate) fn set_run_summary(summary: Option<String>) {
+    if let Ok(mut run_summary) = RUN_SUMMARY.lock() {
+        *run_summary = summary;
+    }
+}
+
+fn crash_report_dir() -> PathBuf {
+    data_dir().join("crash-reports")
+}
+
+fn write_report(info: &PanicHookInfo) -> std::io::Result<PathBuf> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+
+    let location = info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_else(|| "unknown location".to_string());
+
+    // The hook may run while the panicking thread holds the lock.
+    let run_summary = RUN_SUMMARY
+        .try_lock()
+        .ok()
+        .and_then(|summary| summary.clone())
+        .unwrap_or_else(|| "no run in progress".to_string());
+
+    let report = format!(
+        "Moon Landr {} crash report\n\nPanic: {}\nLocation: {}\n\nRun: {}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        message,
+        location,
+        run_summary,
+        Backtrace::force_capture()
+    );
+
+    let dir = crash_report_dir();
+    fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("crash-{}.txt", timestamp));
+    fs::write(&path, report)?;
+    fs::write(
+        dir.join(LAST_CRASH_FILE_NAME),
+        path.to_string_lossy().as_bytes(),
+    )?;
+
+    Ok(path)
+}
+
+fn take_previous_crash() -> Option<PathBuf> {
+    let marker = crash_report_dir().join(LAST_CRASH_FILE_NAME);
+    let path = fs::read_to_string(&marker).ok()?;
+    let _ = fs::remove_file(marker);
+    Some(PathBuf::from(path.trim()))
+}
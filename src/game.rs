@@ -1,16 +1,65 @@
-use crate::*;
+mod audio;
+mod autopilot;
+mod camera;
+mod credits;
+mod emergency;
+mod fleet;
+mod hud;
+mod outcome;
+mod outposts;
+mod player;
+mod practice;
+mod quit_confirm;
+mod recording;
+mod replay;
+mod rival;
+mod servicing;
+mod supply_drop;
+mod traffic;
+mod world;
+
+use credits::DistanceMilestones;
+use emergency::start_emergency;
+use fleet::spawn_fleet;
+use outcome::{LandingResult, LandingZone, LoseReason, WinTimer, par_time, player_crash_observer};
+use player::{
+    ControlInput, DeltaVSpent, Fuel, Grounded, LanderSprite, Player, PlayerState, RcsFuel,
+    lander_body,
+};
+use practice::{PracticePad, practice_spawn};
+use rival::spawn_rival;
+use world::{ExploredTerrain, OccluderMaterial, Terrain, TerrainMaterial};
+
+use crate::{
+    crash_report,
+    heatmap::{HEATMAP_CELL_SIZE, Heatmap, HeatmapOverlay},
+    run_log::RunEvent,
+    stats::{GIMBAL_UNLOCK_LANDINGS, LifetimeStats, RunFinished, RunOutcome},
+    synth,
+    telemetry::{FlightRecorder, TelemetrySample},
+    terrain::{
+        CHUNK_GRANULARITY, CHUNK_WIDTH, PadHazard, PadSite, Surface, SurfaceMaterial,
+        TerrainGenerator, TerrainParams,
+    },
+    typography::TextStyle,
+    *,
+};
 
 use avian2d::{math::PI, prelude::*};
 use bevy::{
     asset::RenderAssetUsages,
+    audio::Volume,
     camera::ScalingMode,
+    ecs::system::SystemParam,
+    input::mouse::AccumulatedMouseScroll,
     mesh::{Indices, PrimitiveTopology},
     prelude::*,
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
 };
-use noiz::prelude::*;
 use rand::{Rng, SeedableRng, rngs::StdRng};
 use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
@@ -24,183 +73,175 @@ use std::{
 
 #[derive(SubStates, Clone, PartialEq, Eq, Hash, Debug, Default)]
 #[source(GameState = GameState::Game)]
-enum GamePhase {
+pub(crate) enum GamePhase {
     #[default]
     Running,
+    Map,
+    /// Asking whether to leave the run for the menu.
+    QuitConfirm,
     Win,
     Lose,
 }
 
-#[derive(Component)]
-struct Player;
-
-#[derive(Component, PartialEq, Eq)]
-enum PlayerState {
-    Idle,
-    Firing,
-    Crashed,
+/// Steps of each frame of flight, in order, for the game modules to slot their
+/// systems into.
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+enum FlightSystems {
+    /// Commands from the player.
+    Input,
+    /// Computer pilots and stability assists.
+    Assist,
+    /// Engine forces and flight envelope limits.
+    Thrust,
+    /// Streaming terrain in around the camera.
+    Terrain,
+    Camera,
+    /// Ground contact.
+    Contact,
+    /// Deciding whether landers have landed.
+    Landing,
 }
 
-#[derive(Component)]
-struct Fuel(u32);
-
-#[derive(Component)]
-struct ScoreMultiplier(f32);
-
-#[derive(Resource)]
-struct WinTimer(Timer);
+#[derive(PhysicsLayer, Default)]
+enum GameLayer {
+    #[default]
+    Terrain,
+    Lander,
+}
 
 #[derive(Resource)]
 struct TimePassed(Duration);
 
 #[derive(Component)]
-enum HudText {
-    Fuel,
-    XVelocity,
-    YVelocity,
-    TimePassed,
-}
-
-#[derive(Component)]
-struct Ground;
-
-#[derive(Component)]
-struct Grounded(bool);
-
-#[derive(Component)]
-struct TerrainChunk {
-    x_origin: f32,
-}
-
-#[derive(Component)]
-struct LandPad {
-    score_multiplier: f32,
-}
-
-type TerrainNoiseType = Noise<
-    LayeredNoise<
-        Normed<f32>,
-        Persistence,
-        FractalLayers<Octave<MixCellGradients<OrthoGrid, Smoothstep, QuickGradients>>>,
-    >,
->;
+struct BackgroundSky;
 
 #[derive(Resource)]
-struct TerrainNoiseGenerator(TerrainNoiseType);
+struct TerrainSeed(u32);
 
+/// How the player lander started the run, which its landing is scored against.
 #[derive(Resource)]
-struct TerrainMaterial(Handle<ColorMaterial>);
+struct RunStart {
+    position: Vec2,
+    velocity: Vec2,
+    /// Rotation around Z, 0.0 when upright.
+    angle: f32,
+    angular_velocity: f32,
+    fuel: u32,
+    /// Seconds before the main engine can first fire.
+    engine_out: f32,
+    /// Time the autopilot takes to land from the same start, if it can.
+    par: Option<f32>,
+}
 
+/// Seed to reuse for the next run instead of rolling a new one.
 #[derive(Resource)]
-struct OccluderMaterial(Handle<ColorMaterial>);
+pub(crate) struct RetrySeed(u32);
 
-#[derive(Resource)]
-struct GameSounds {
-    thrust_sound: Handle<AudioSource>,
-    crash_sound: Handle<AudioSource>,
-    landing_sound: Handle<AudioSource>,
-}
+/// Places where the player crashed during this session, keyed by terrain seed.
+#[derive(Resource, Default)]
+struct CrashSites(HashMap<u32, Vec<Transform>>);
 
 #[derive(Component)]
-enum GameSound {
-    Thrust,
-    Crash,
-    Landing,
-}
-
-#[derive(Component)]
-struct BackgroundSky;
+struct Wreck;
 
 const GRAVITY: Vec2 = Vec2::new(0.0, -1.62);
 const THRUST: f32 = 12000.0;
 const ROTATION_THRUST: f32 = 3.0;
-const FUEL_CONSUMPTION_RATE: u32 = 1;
 const SAFE_LANDING_IMPULSE_MAGNITUDE: f32 = 15000.0;
+const MIN_FUEL_LEAK_RATE: f32 = 5.0;
+
+const LEAK_PARTICLE_INTERVAL: f32 = 0.05;
+
+const MAX_RCS_FUEL: f32 = 100.0;
+
 const FUEL_MASS_FACTOR: f32 = 1.0;
 const DRY_LANDER_MASS: f32 = 800.0;
 const MAX_FUEL: u32 = 1000;
 
-const CHUNK_BUFFER_OUTSIDE_VIEWPORT_COUNT: i32 = 3;
-const CHUNK_WIDTH: f32 = 400.0;
-const CHUNK_GRANULARITY: u32 = 2; // units per sample point
-const CHUNK_NOISE_LAYERS: u32 = 12;
-const CHUNK_NOISE_PERSISTENCE: f32 = 0.7;
-const CHUNK_NOISE_LACUNARITY: f32 = 2.0;
-const CHUNK_NOISE_FREQUENCY: f32 = CHUNK_GRANULARITY as f32 / CHUNK_WIDTH;
-const CHUNK_HEIGHT_AMPLITUDE: f32 = 400.0;
-const CHUNK_BASE_HEIGHT: f32 = 400.0;
-
 const CAMERA_VIEWPORT_WIDTH: f32 = 1600.0;
 const CAMERA_VIEWPORT_HEIGHT: f32 = 900.0;
 
 const LANDER_SIZE: UVec2 = UVec2::new(16, 16);
-const LAND_PAD_WIDTHS: [u32; 3] = [16, 24, 32]; // in world units, for small, medium, large pads
-const LAND_PAD_MULTIPLIERS: [f32; 3] = [5.0, 3.0, 2.0];
-
-const INITIAL_HORIZONTAL_SPEED: f32 = 50.0;
+const LAND_PAD_SENSOR_HEIGHT: f32 = 16.0;
 
-const WIN_TIMER_DURATION: f32 = 3.0;
-
-const STAR_DENSITY: f32 = 0.0005;
+const ICE_COLOR: Color = Color::srgb(0.6, 0.85, 1.0);
+const SOFT_REGOLITH_COLOR: Color = Color::srgb(0.85, 0.75, 0.6);
 
-const MAX_TIME_SCORE_BONUS: f32 = 1000.0;
-const TIME_SCORE_BONUS_REDUCTION_FACTOR: f32 = MAX_TIME_SCORE_BONUS / 200.0; // reduces to zero at 200 seconds
+const OPERATIONAL_ZONE_WARNING_HEIGHT: f32 = 950.0;
+const OPERATIONAL_ZONE_ABORT_HEIGHT: f32 = 1300.0;
 
-const FUEL_SCORE_BONUS_FACTOR: f32 = 2.0; // each unit of fuel left gives this much score
+const INITIAL_HORIZONTAL_SPEED: f32 = 50.0;
+const SPAWN_HEIGHT: f32 = 850.0;
 
-const MAX_HEIGHT_SCORE_BONUS_FACTOR: f32 = 1.0; // each unit of height at landing gives this much score
+const STAR_DENSITY: f32 = 0.0005;
 
 pub(crate) fn plugin(app: &mut App) {
     app.add_sub_state::<GamePhase>()
+        .init_resource::<CrashSites>()
+        .configure_sets(
+            Update,
+            (
+                FlightSystems::Input,
+                FlightSystems::Assist,
+                FlightSystems::Thrust,
+                FlightSystems::Terrain,
+                FlightSystems::Camera,
+                FlightSystems::Contact,
+                FlightSystems::Landing,
+            )
+                .chain(),
+        )
+        .add_plugins((
+            player::plugin,
+            world::plugin,
+            camera::plugin,
+            hud::plugin,
+            audio::plugin,
+            outcome::plugin,
+            supply_drop::plugin,
+            emergency::plugin,
+            fleet::plugin,
+            outposts::plugin,
+            practice::plugin,
+            replay::plugin,
+            traffic::plugin,
+            rival::plugin,
+            servicing::plugin,
+            credits::plugin,
+            recording::plugin,
+            quit_confirm::plugin,
+        ))
         .add_systems(OnEnter(GameState::Game), setup_level)
         .add_systems(
             Update,
             (
-                (
-                    (
-                        control_system,
-                        audio_system,
-                        terrain_chunk_system,
-                        camera_follow_system,
-                        ground_detection_system,
-                        start_win_timer_system,
-                        reset_win_timer_system,
-                        tick_win_timer_system,
-                    )
-                        .chain(),
-                    fuel_weight_system,
-                    playtime_system,
-                )
-                    .run_if(in_state(GamePhase::Running)),
-                (end_input_system).run_if(not(in_state(GamePhase::Running))),
-                animation_system,
-                hud_system,
+                playtime_system.run_if(in_state(GamePhase::Running)),
+                end_input_system.run_if(in_state(GamePhase::Win).or(in_state(GamePhase::Lose))),
             )
                 .run_if(in_state(GameState::Game)),
         )
-        .add_systems(OnExit(GameState::Game), cleanup_level)
-        .add_systems(OnEnter(GamePhase::Lose), setup_lose_screen)
-        .add_systems(OnExit(GamePhase::Lose), cleanup_lose_screen)
-        .add_systems(OnEnter(GamePhase::Win), setup_win_screen)
-        .add_systems(OnExit(GamePhase::Win), cleanup_win_screen);
+        .add_systems(OnExit(GameState::Game), cleanup_level);
 }
 
 fn setup_level(
     mut commands: Commands,
     mut clear_color: ResMut<ClearColor>,
     asset_server: Res<AssetServer>,
-    font: Res<MainFont>,
     mut camera: Single<(&mut Transform, &mut Projection), With<Camera>>,
     mut layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    retry_seed: Option<Res<RetrySeed>>,
+    rules: Res<GameRules>,
+    mut run_events: MessageWriter<RunEvent>,
+    mut practice_pad: ResMut<PracticePad>,
     /*mut meshes: ResMut<Assets<Mesh>>,*/
 ) {
     let Projection::Orthographic(perspective) = camera.1.as_mut() else {
         return;
     };
 
-    let font = &font.0;
+    let terrain_params = TerrainParams::default();
 
     clear_color.0 = Color::BLACK;
 
@@ -217,16 +258,20 @@ fn setup_level(
 
     let layout_handle = layouts.add(layout);
 
-    commands
+    let lander_sprite = LanderSprite {
+        texture: texture.clone(),
+        layout: layout_handle.clone(),
+    };
+
+    let player = commands
         .spawn((
             DespawnOnExit(GameState::Game),
             Player,
             Grounded(false),
-            ScoreMultiplier(1.0),
-            RigidBody::Dynamic,
-            CollisionEventsEnabled,
-            Collider::rectangle(LANDER_SIZE.x as f32, LANDER_SIZE.y as f32),
-            Mass(DRY_LANDER_MASS + (MAX_FUEL as f32 * FUEL_MASS_FACTOR)),
+            LandingZone(None),
+            DeltaVSpent(0.0),
+            ControlInput::default(),
+            lander_body(terrain_params.lander_leg_material),
             Sprite::from_atlas_image(
                 texture,
                 TextureAtlas {
@@ -238,35 +283,107 @@ fn setup_level(
             Fuel(MAX_FUEL),
             Transform {
                 rotation: Quat::from_rotation_z(PI / 2.0),
-                translation: Vec3::new(0.0, 850.0, 0.0),
+                translation: Vec3::new(0.0, SPAWN_HEIGHT, 0.0),
                 ..Default::default()
             },
             LinearVelocity {
                 0: Vec2::new(INITIAL_HORIZONTAL_SPEED, 0.0),
             },
         ))
-        .observe(player_crash_observer);
-
-    let seed = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos() as u32;
-
-    let mut terrain_noise_generator: TerrainNoiseType = Noise::from(LayeredNoise::new(
-        Normed::<f32>::default(),
-        Persistence(CHUNK_NOISE_PERSISTENCE),
-        FractalLayers {
-            layer: Octave::<MixCellGradients<OrthoGrid, Smoothstep, QuickGradients>>::default(),
-            lacunarity: CHUNK_NOISE_LACUNARITY,
-            amount: CHUNK_NOISE_LAYERS,
-        },
-    ));
-    terrain_noise_generator.set_seed(seed);
-    terrain_noise_generator.set_frequency(CHUNK_NOISE_FREQUENCY);
+        .observe(player_crash_observer)
+        .id();
+
+    if rules.realism {
+        commands.entity(player).insert(RcsFuel::full());
+    }
+
+    if rules.fleet {
+        spawn_fleet(
+            &mut commands,
+            player,
+            &lander_sprite,
+            terrain_params.lander_leg_material,
+            &rules,
+        );
+    }
+
+    if rules.rival {
+        spawn_rival(
+            &mut commands,
+            &lander_sprite,
+            terrain_params.lander_leg_material,
+            &rules,
+        );
+    }
+
+    commands.insert_resource(lander_sprite);
+
+    let seed = match retry_seed {
+        Some(retry_seed) => {
+            commands.remove_resource::<RetrySeed>();
+            retry_seed.0
+        }
+        None => {
+            practice_pad.0 = 0;
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u32
+        }
+    };
+
+    commands.insert_resource(TerrainSeed(seed));
+
+    run_events.write(RunEvent::Started { seed });
+
+    let terrain_generator = TerrainGenerator::new(seed, terrain_params);
+
+    let mut run_start = RunStart {
+        position: Vec2::new(0.0, SPAWN_HEIGHT),
+        velocity: Vec2::new(INITIAL_HORIZONTAL_SPEED, 0.0),
+        angle: PI / 2.0,
+        angular_velocity: 0.0,
+        fuel: MAX_FUEL,
+        engine_out: 0.0,
+        par: None,
+    };
+    let mut par_pad = 0;
 
-    commands.insert_resource(TerrainNoiseGenerator(terrain_noise_generator));
+    if rules.practice
+        && let Some(spawn) = practice_spawn(&terrain_generator, &practice_pad)
+    {
+        commands.entity(player).insert((
+            Transform::from_translation(spawn.extend(0.0)),
+            LinearVelocity(rules.practice_velocity),
+        ));
+        camera.0.translation.x = spawn.x;
+        commands.insert_resource(DistanceMilestones {
+            furthest: spawn.x.abs(),
+        });
+        run_start.position = spawn;
+        run_start.velocity = rules.practice_velocity;
+        par_pad = practice_pad.0;
+    }
+
+    if rules.emergency_starts && !rules.practice && !rules.fleet {
+        start_emergency(&mut commands, player, seed, &mut run_start);
+    }
 
-    commands.insert_resource(TerrainMaterial(materials.add(Color::WHITE)));
+    run_start.par = par_time(
+        &terrain_generator,
+        par_pad,
+        &run_start,
+        &rules.win_tolerances,
+    );
+    commands.insert_resource(run_start);
+
+    commands.insert_resource(Terrain(terrain_generator));
+
+    commands.insert_resource(TerrainMaterial {
+        regolith: materials.add(Color::WHITE),
+        ice: materials.add(ICE_COLOR),
+        soft_regolith: materials.add(SOFT_REGOLITH_COLOR),
+    });
 
     commands.insert_resource(OccluderMaterial(materials.add(Color::BLACK)));
 
@@ -330,65 +447,6 @@ fn setup_level(
         ]),
     ));*/
 
-    commands.spawn((
-        DespawnOnExit(GameState::Game),
-        Node {
-            position_type: PositionType::Absolute,
-            top: Val::Px(10.0),
-            right: Val::Px(10.0),
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            column_gap: Val::Px(5.0),
-            ..Default::default()
-        },
-        children![
-            (
-                HudText::TimePassed,
-                Text::new("TIME PASSED: 0.0 s"),
-                TextColor(Color::WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-                TextFont {
-                    font_size: 16.0,
-                    font: font.clone(),
-                    ..default()
-                },
-            ),
-            (
-                HudText::Fuel,
-                Text::new("FUEL: 100"),
-                TextColor(Color::WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-                TextFont {
-                    font_size: 16.0,
-                    font: font.clone(),
-                    ..default()
-                },
-            ),
-            (
-                HudText::XVelocity,
-                Text::new("HORIZONTAL VELOCITY: 0.0 m/s"),
-                TextColor(Color::WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-                TextFont {
-                    font_size: 16.0,
-                    font: font.clone(),
-                    ..default()
-                },
-            ),
-            (
-                HudText::YVelocity,
-                Text::new("VERTICAL VELOCITY: 0.0 m/s"),
-                TextColor(Color::WHITE),
-                TextLayout::new_with_justify(Justify::Right),
-                TextFont {
-                    font_size: 16.0,
-                    font: font.clone(),
-                    ..default()
-                },
-            ),
-        ],
-    ));
-
     let mut rng = StdRng::seed_from_u64(seed as u64);
 
     // RGBA8 texture
@@ -439,17 +497,13 @@ fn setup_level(
     ));
 
     commands.insert_resource(WinTimer(Timer::from_seconds(
-        WIN_TIMER_DURATION,
+        rules.win_tolerances.settle_time,
         TimerMode::Once,
     )));
 
     commands.insert_resource(TimePassed(Duration::ZERO));
 
-    commands.insert_resource(GameSounds {
-        thrust_sound: asset_server.load("sounds/engine.wav"),
-        crash_sound: asset_server.load("sounds/explosion.wav"),
-        landing_sound: asset_server.load("sounds/win.wav"),
-    });
+    commands.init_resource::<ExploredTerrain>();
 
     commands.insert_resource(Gravity(GRAVITY));
 }
@@ -468,625 +522,43 @@ fn cleanup_level(
 
     commands.remove_resource::<WinTimer>();
 
+    commands.remove_resource::<LandingResult>();
+
     commands.remove_resource::<TimePassed>();
 
-    commands.remove_resource::<TerrainNoiseGenerator>();
+    commands.remove_resource::<Terrain>();
 
     commands.remove_resource::<TerrainMaterial>();
 
     commands.remove_resource::<OccluderMaterial>();
 
-    commands.remove_resource::<GameSounds>();
-
-    commands.insert_resource(Gravity(Vec2::NEG_Y * 9.81));
-}
-
-fn terrain_mesh(heights: &[f32], width: f32) -> Mesh {
-    let mut positions = Vec::new();
-    let mut indices = Vec::new();
-
-    for (i, &h) in heights.iter().enumerate() {
-        let x = i as f32 * width;
-
-        positions.push([x, 0.0, 0.0]);
-        positions.push([x, h, 0.0]);
-    }
-
-    for i in 0..heights.len() - 1 {
-        let base = (i * 2) as u32;
-
-        indices.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
-    }
-
-    let mut mesh = Mesh::new(
-        PrimitiveTopology::TriangleList,
-        RenderAssetUsages::RENDER_WORLD,
-    );
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-    mesh.insert_indices(Indices::U32(indices));
-
-    mesh
-}
-
-fn create_terrain_chunk(
-    commands: &mut Commands,
-    x_origin: f32,
-    terrain_noise_generator: &TerrainNoiseGenerator,
-    terrain_material: &Handle<ColorMaterial>,
-    occluder_material: &Handle<ColorMaterial>,
-    font: &Handle<Font>,
-    meshes: &mut ResMut<Assets<Mesh>>,
-) {
-    let mut ground_heights: Vec<f32> = (0..=CHUNK_WIDTH as i32)
-        .step_by(CHUNK_GRANULARITY as usize)
-        .map(|x| {
-            terrain_noise_generator
-                .0
-                .sample_for::<f32>(Vec2::new(x_origin + x as f32, 0.0))
-                * CHUNK_HEIGHT_AMPLITUDE
-                + CHUNK_BASE_HEIGHT
-        })
-        .collect();
-
-    let seed = x_origin;
-    let mut rng = StdRng::seed_from_u64(seed as u64);
-
-    let mut land_pad: Option<(Vec2, u32, f32)> = None;
-
-    if rng.random_bool(0.7) {
-        let land_pad_type_index = rng.random_range(0..LAND_PAD_WIDTHS.len());
-
-        let land_pad_width = LAND_PAD_WIDTHS[land_pad_type_index];
-
-        let land_pad_window: usize = (land_pad_width / CHUNK_GRANULARITY) as usize;
-
-        let land_pad_multiplier = LAND_PAD_MULTIPLIERS[land_pad_type_index];
-
-        for i in 1..(ground_heights.len() - land_pad_window) {
-            let x_0 = i;
-            let x_1 = i + land_pad_window;
-            if (ground_heights[x_0] - ground_heights[x_1]).abs() <= 4.0 {
-                let pad_height = (ground_heights[x_0] + ground_heights[x_1]) / 2.0;
-                for x in x_0..=x_1 {
-                    ground_heights[x] = pad_height;
-                }
-                let pad_x = (x_0 as f32 + x_1 as f32) * CHUNK_GRANULARITY as f32 / 2.0;
-                land_pad = Some((
-                    Vec2::new(pad_x, pad_height),
-                    land_pad_width,
-                    land_pad_multiplier,
-                ));
-                break;
-            }
-        }
-    }
+    commands.remove_resource::<TerrainSeed>();
 
-    let ground_points: Vec<Vec2> = ground_heights
-        .iter()
-        .enumerate()
-        .map(|(x, &height)| Vec2::new((x * CHUNK_GRANULARITY as usize) as f32, height))
-        .collect();
+    commands.remove_resource::<RunStart>();
 
-    let ground_mesh = meshes.add(Polyline2d::new(ground_points.clone()));
+    commands.remove_resource::<LanderSprite>();
 
-    let occluder_mesh = meshes.add(terrain_mesh(&ground_heights, CHUNK_GRANULARITY as f32));
+    commands.remove_resource::<LoseReason>();
 
-    let mut chunk = commands.spawn((
-        DespawnOnExit(GameState::Game),
-        Ground,
-        TerrainChunk { x_origin },
-        RigidBody::Static,
-        //Collider::heightfield(ground_heights, Vec2::new(1.0, 1.0)),
-        Collider::polyline(ground_points, None), // TODO: should use heightfield or similar for performance
-        Mesh2d(ground_mesh),
-        MeshMaterial2d(terrain_material.clone()),
-        Transform::from_translation(Vec3::new(x_origin + CHUNK_WIDTH as f32 / 2.0, 0.0, 0.0)),
-    ));
-
-    chunk.with_child((
-        Mesh2d(occluder_mesh),
-        MeshMaterial2d(occluder_material.clone()),
-    ));
+    commands.remove_resource::<ExploredTerrain>();
 
-    if let Some(pad_pos) = land_pad {
-        chunk.with_children(|parent| {
-            parent
-                .spawn((
-                    LandPad {
-                        score_multiplier: pad_pos.2,
-                    },
-                    RigidBody::Static,
-                    Sensor,
-                    CollisionEventsEnabled,
-                    Collider::rectangle(pad_pos.1 as f32, 16.0),
-                    Transform::from_translation(Vec3::new(pad_pos.0.x, pad_pos.0.y + 8.0, 0.0)),
-                    Visibility::default(),
-                ))
-                .observe(player_entered_landing_zone)
-                .observe(player_exited_landing_zone)
-                .with_child((
-                    Text2d::new(format!("x{:.1}", pad_pos.2)),
-                    TextFont {
-                        font_size: 12.0,
-                        font: font.clone(),
-                        ..default()
-                    },
-                    TextLayout::new_with_justify(Justify::Center),
-                    TextColor(Color::WHITE),
-                    Transform::from_translation(Vec3::new(0.0, -24.0, 0.0)),
-                ));
-        });
-    }
-}
-
-fn terrain_chunk_system(
-    mut commands: Commands,
-    player: Single<&Transform, With<Player>>,
-    existing_chunks: Query<(Entity, &TerrainChunk)>,
-    terrain_noise_generator: Res<TerrainNoiseGenerator>,
-    terrain_material: Res<TerrainMaterial>,
-    occluder_material: Res<OccluderMaterial>,
-    font: Res<MainFont>,
-    mut meshes: ResMut<Assets<Mesh>>,
-) {
-    let player_x = player.translation.x;
-    let current_chunk_x_origin: i32 = ((player_x / CHUNK_WIDTH).floor() * CHUNK_WIDTH) as i32;
-
-    const CHUNKS_IN_CAMERA_VIEWPORT: i32 = (CAMERA_VIEWPORT_WIDTH / CHUNK_WIDTH).ceil() as i32 + 2; // +2 for buffer on each side
-
-    let needed_chunk_origins: Vec<i32> = ((-CHUNK_BUFFER_OUTSIDE_VIEWPORT_COUNT
-        - CHUNKS_IN_CAMERA_VIEWPORT / 2)
-        ..(CHUNKS_IN_CAMERA_VIEWPORT / 2 + CHUNK_BUFFER_OUTSIDE_VIEWPORT_COUNT))
-        .map(|i| current_chunk_x_origin + (i * CHUNK_WIDTH as i32))
-        .collect::<Vec<i32>>();
-
-    // Remove chunks that are no longer needed
-    for (entity, chunk) in existing_chunks.iter() {
-        let chunk_x_origin_i32 = chunk.x_origin as i32;
-        if !needed_chunk_origins.contains(&chunk_x_origin_i32) {
-            commands.entity(entity).despawn();
-        }
-    }
-
-    let exisitng_chunk_origins: Vec<i32> = existing_chunks
-        .iter()
-        .map(|(_, chunk)| chunk.x_origin as i32)
-        .collect::<Vec<i32>>();
-
-    let chunks_to_add: Vec<f32> = needed_chunk_origins
-        .iter()
-        .cloned()
-        .filter_map(|x_origin| {
-            if !exisitng_chunk_origins.contains(&x_origin) {
-                Some(x_origin as f32)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<f32>>();
-
-    for x_origin in chunks_to_add {
-        create_terrain_chunk(
-            &mut commands,
-            x_origin,
-            &terrain_noise_generator,
-            &terrain_material.0,
-            &occluder_material.0,
-            &font.0,
-            &mut meshes,
-        );
-    }
-}
-
-fn camera_follow_system(
-    player: Single<&Transform, With<Player>>,
-    mut background: Single<&mut Transform, (With<BackgroundSky>, Without<Player>)>,
-    mut camera: Single<
-        (&mut Transform, &Projection),
-        (With<Camera>, Without<Player>, Without<BackgroundSky>),
-    >,
-    window: Single<&Window>,
-) {
-    let Projection::Orthographic(perspective) = camera.1 else {
-        return;
-    };
-
-    let viewport_size = Vec2::new(window.width(), window.height()) * perspective.scale;
-
-    let center = camera.0.translation.truncate();
-    let quarter_size = viewport_size / 4.0;
-
-    let min = center - quarter_size;
-    let max = center + quarter_size;
-
-    if player.translation.x < min.x {
-        camera.0.translation.x = player.translation.x + quarter_size.x;
-    } else if player.translation.x > max.x {
-        camera.0.translation.x = player.translation.x - quarter_size.x;
-    }
-
-    background.translation.x = camera.0.translation.x;
+    commands.insert_resource(Gravity(Vec2::NEG_Y * 9.81));
 }
 
 fn end_input_system(
+    mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    terrain_seed: Res<TerrainSeed>,
     mut game_state: ResMut<NextState<GameState>>,
 ) {
     if keyboard_input.just_pressed(KeyCode::Space) {
         game_state.set(GameState::Menu);
-    }
-}
-
-fn control_system(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut player: Single<(&Transform, Forces, &mut PlayerState, &mut Fuel), With<Player>>,
-    mut game_state: ResMut<NextState<GameState>>,
-) {
-    if keyboard_input.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
-        player.1.apply_angular_acceleration(ROTATION_THRUST);
-    }
-    if keyboard_input.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
-        player.1.apply_angular_acceleration(-ROTATION_THRUST);
-    }
-
-    if player.3.0 > 0 {
-        if keyboard_input.just_pressed(KeyCode::Space) {
-            *player.2 = PlayerState::Firing;
-        }
-        if keyboard_input.pressed(KeyCode::Space) {
-            let force_vector = (player.0.rotation * Vec3::Y * THRUST).truncate();
-
-            player.1.apply_force(force_vector);
-            player.3.0 = player.3.0.saturating_sub(FUEL_CONSUMPTION_RATE);
-        }
-    }
-    if (keyboard_input.just_released(KeyCode::Space) && *player.2 == PlayerState::Firing)
-        || player.3.0 == 0
-    {
-        *player.2 = PlayerState::Idle;
-    }
-
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    } else if keyboard_input.just_pressed(KeyCode::KeyR) {
+        commands.insert_resource(RetrySeed(terrain_seed.0));
         game_state.set(GameState::Menu);
     }
 }
 
-fn animation_system(
-    mut player: Single<(&PlayerState, &mut Sprite), (With<Player>, Changed<PlayerState>)>,
-) {
-    match player.0 {
-        PlayerState::Idle => {
-            player.1.texture_atlas.as_mut().unwrap().index = 0;
-        }
-        PlayerState::Firing => {
-            player.1.texture_atlas.as_mut().unwrap().index = 1;
-        }
-        PlayerState::Crashed => {
-            player.1.texture_atlas.as_mut().unwrap().index = 2;
-        }
-    }
-}
-
-fn audio_system(
-    mut commands: Commands,
-    player: Single<&PlayerState, (With<Player>, Changed<PlayerState>)>,
-    game_sounds: Res<GameSounds>,
-    sounds_query: Query<(Entity, &AudioSink, &GameSound)>,
-) {
-    match *player {
-        PlayerState::Firing => {
-            commands.spawn((
-                DespawnOnExit(GamePhase::Running),
-                GameSound::Thrust,
-                AudioPlayer::new(game_sounds.thrust_sound.clone()),
-                PlaybackSettings::LOOP,
-            ));
-        }
-        _ => {
-            for (entity, sink, sound) in &sounds_query {
-                match sound {
-                    GameSound::Thrust => {
-                        sink.stop();
-                        commands.entity(entity).despawn();
-                    }
-                    _ => {}
-                }
-            }
-        }
-    }
-}
-
 fn playtime_system(time: Res<Time>, mut time_passed: ResMut<TimePassed>) {
     time_passed.0 += time.delta();
 }
-
-fn hud_system(
-    player: Single<(&LinearVelocity, &Fuel), With<Player>>,
-    time_passed: Res<TimePassed>,
-    mut texts_query: Query<(&HudText, &mut Text)>,
-) {
-    for (kind, mut text) in &mut texts_query {
-        match kind {
-            HudText::Fuel => {
-                text.0 = format!("FUEL: {}", player.1.0);
-            }
-            HudText::XVelocity => {
-                let horizontal_velocity = player.0.0.x;
-                text.0 = format!("HORIZONTAL VELOCITY: {:.1} m/s", horizontal_velocity);
-            }
-            HudText::YVelocity => {
-                let vertical_velocity = player.0.0.y;
-                text.0 = format!("VERTICAL VELOCITY: {:.1} m/s", vertical_velocity);
-            }
-            HudText::TimePassed => {
-                let total_secs = time_passed.0.as_secs();
-                let minutes = total_secs / 60;
-                let seconds = total_secs % 60;
-                text.0 = format!("TIME PASSED: {:02}:{:02}", minutes, seconds);
-            }
-        }
-    }
-}
-
-fn player_entered_landing_zone(
-    event: On<CollisionStart>,
-    landpads: Query<&LandPad>,
-    mut player: Single<(&mut ScoreMultiplier, Entity), With<Player>>,
-) {
-    let this_entity = event.collider1;
-    let other_entity = event.collider2;
-
-    let Ok(land_pad) = landpads.get(this_entity) else {
-        return;
-    };
-
-    if player.1 != other_entity {
-        return;
-    };
-
-    player.0.0 = land_pad.score_multiplier;
-}
-
-fn player_exited_landing_zone(
-    event: On<CollisionEnd>,
-    mut player: Single<(&mut ScoreMultiplier, Entity), With<Player>>,
-) {
-    let other_entity = event.collider2;
-
-    if player.1 != other_entity {
-        return;
-    };
-
-    player.0.0 = 1.0;
-}
-
-fn ground_detection_system(
-    mut collision_started: MessageReader<CollisionStart>,
-    mut collision_ended: MessageReader<CollisionEnd>,
-    ground_query: Query<(), With<Ground>>,
-    mut grounded_query: Query<&mut Grounded /*, With<Player>*/>,
-) {
-    for event in collision_started.read() {
-        let (a, b) = (event.collider1, event.collider2);
-
-        let grounded_entity: Entity = if grounded_query.get(a).is_ok() {
-            a
-        } else if grounded_query.get(b).is_ok() {
-            b
-        } else {
-            continue;
-        };
-
-        let other = if grounded_entity == a { b } else { a };
-        if ground_query.get(other).is_ok() {
-            if let Ok(mut grounded) = grounded_query.get_mut(grounded_entity) {
-                grounded.0 = true;
-            }
-        }
-    }
-
-    for event in collision_ended.read() {
-        let (a, b) = (event.collider1, event.collider2);
-
-        let grounded_entity: Entity = if grounded_query.get(a).is_ok() {
-            a
-        } else if grounded_query.get(b).is_ok() {
-            b
-        } else {
-            continue;
-        };
-
-        let other = if grounded_entity == a { b } else { a };
-        if ground_query.get(other).is_ok() {
-            if let Ok(mut grounded) = grounded_query.get_mut(grounded_entity) {
-                grounded.0 = false;
-            }
-        }
-    }
-}
-
-fn player_crash_observer(
-    event: On<CollisionStart>,
-    player: Single<Entity, With<Player>>,
-    ground_query: Query<Entity, With<Ground>>,
-    collisions: Collisions,
-    mut game_phase: ResMut<NextState<GamePhase>>,
-) {
-    let (a, b) = (event.collider1, event.collider2);
-
-    let player_entity = if a == *player {
-        a
-    } else if b == *player {
-        b
-    } else {
-        return;
-    };
-
-    let other_entity = if player_entity == a { b } else { a };
-
-    if ground_query.get(other_entity).is_err() {
-        return;
-    }
-
-    let mut impact_impulse_magnitude = 0.0;
-    for contact_pair in collisions.collisions_with(player_entity) {
-        impact_impulse_magnitude += contact_pair.total_normal_impulse_magnitude();
-    }
-
-    if impact_impulse_magnitude > SAFE_LANDING_IMPULSE_MAGNITUDE {
-        game_phase.set(GamePhase::Lose);
-    }
-}
-
-fn tick_win_timer_system(
-    time: Res<Time>,
-    mut win_timer: ResMut<WinTimer>,
-    mut game_phase: ResMut<NextState<GamePhase>>,
-) {
-    win_timer.0.tick(time.delta());
-    if win_timer.0.just_finished() {
-        game_phase.set(GamePhase::Win);
-    }
-}
-
-fn start_win_timer_system(
-    player: Single<(&Grounded, &LinearVelocity, &AngularVelocity, &Transform), With<Player>>,
-    mut win_timer: ResMut<WinTimer>,
-) {
-    if win_timer.0.is_paused()
-        && (player.0.0
-            && player.1.0.length() < 5.0
-            && player.2.0.abs() < 0.1
-            && player.3.rotation.to_euler(EulerRot::XYZ).2.abs() < PI / 2.0)
-    {
-        win_timer.0.reset();
-        win_timer.0.unpause();
-    }
-}
-
-fn reset_win_timer_system(
-    player: Single<(&Grounded, &LinearVelocity, &AngularVelocity, &Transform), With<Player>>,
-    mut win_timer: ResMut<WinTimer>,
-) {
-    if !win_timer.0.is_paused()
-        && (!player.0.0
-            || player.1.0.length() >= 5.0
-            || player.2.0.abs() >= 0.1
-            || player.3.rotation.to_euler(EulerRot::XYZ).2.abs() >= PI / 2.0)
-    {
-        win_timer.0.pause();
-    }
-}
-
-fn fuel_weight_system(mut player: Single<(&mut Mass, &Fuel), (With<Player>, Changed<Fuel>)>) {
-    let empty_mass = DRY_LANDER_MASS;
-    let fuel_mass = player.1.0 as f32 * FUEL_MASS_FACTOR;
-    player.0.0 = empty_mass + fuel_mass;
-}
-
-fn setup_lose_screen(
-    mut commands: Commands,
-    mut player: Single<
-        (
-            Entity,
-            &mut PlayerState,
-            &mut LinearVelocity,
-            &mut AngularVelocity,
-        ),
-        With<Player>,
-    >,
-    font: Res<MainFont>,
-    game_sounds: Res<GameSounds>,
-) {
-    let font = &font.0;
-
-    *player.1 = PlayerState::Crashed;
-    player.2.0 = Vec2::ZERO;
-    player.3.0 = 0.0;
-    commands.entity(player.0).insert(LockedAxes::ALL_LOCKED);
-
-    commands.spawn((
-        DespawnOnExit(GamePhase::Lose),
-        Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            ..Default::default()
-        },
-        children![(
-            Text::new("You Lost!\nPress SPACE to return to menu."),
-            TextColor(Color::WHITE),
-            TextLayout::new_with_justify(Justify::Center),
-            TextFont {
-                font_size: 48.0,
-                font: font.clone(),
-                ..default()
-            },
-            TextBackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
-        )],
-    ));
-
-    commands.spawn((
-        DespawnOnExit(GamePhase::Lose),
-        GameSound::Crash,
-        AudioPlayer::new(game_sounds.crash_sound.clone()),
-        PlaybackSettings::DESPAWN,
-    ));
-}
-
-fn cleanup_lose_screen(mut _commands: Commands) {}
-
-fn setup_win_screen(
-    mut commands: Commands,
-    player: Single<(&ScoreMultiplier, &Fuel, &Transform), With<Player>>,
-    time_passed: Res<TimePassed>,
-    font: Res<MainFont>,
-    game_sounds: Res<GameSounds>,
-) {
-    let font = &font.0;
-
-    let score = ((MAX_TIME_SCORE_BONUS
-        - time_passed.0.as_secs_f32() * TIME_SCORE_BONUS_REDUCTION_FACTOR)
-        + (player.1.0 as f32 * FUEL_SCORE_BONUS_FACTOR)
-        + (player.2.translation.y * MAX_HEIGHT_SCORE_BONUS_FACTOR))
-        * player.0.0;
-
-    commands.spawn((
-        DespawnOnExit(GamePhase::Win),
-        Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            display: Display::Flex,
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            ..Default::default()
-        },
-        children![(
-            Text::new(format!(
-                "You Landed Successfully!\nPress SPACE to return to menu.\nScore: {:.2}",
-                score
-            )),
-            TextColor(Color::WHITE),
-            TextLayout::new_with_justify(Justify::Center),
-            TextFont {
-                font_size: 48.0,
-                font: font.clone(),
-                ..default()
-            },
-            TextBackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
-        )],
-    ));
-
-    commands.spawn((
-        DespawnOnExit(GamePhase::Win),
-        GameSound::Landing,
-        AudioPlayer::new(game_sounds.landing_sound.clone()),
-        PlaybackSettings::DESPAWN,
-    ));
-}
-
-fn cleanup_win_screen(mut _commands: Commands) {}
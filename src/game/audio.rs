@@ -0,0 +1,387 @@
+//! Sound effects, ambience, and music stems.
+
+use super::{
+    hud::drift_ratio,
+    world::{LandPad, LandPadKind, TerrainQuery},
+    *,
+};
+
+const RCS_SOUND_VOLUME: f32 = 0.3;
+const RCS_SOUND_SPEED: f32 = 2.5; // playback speed of the engine sound, for a hiss
+const TOUCHDOWN_SOUND_VOLUME: f32 = 0.2;
+const TOUCHDOWN_SOUND_SPEED: f32 = 1.8; // playback speed of the crash sound, for a thud
+const WARNING_SOUND_SPEED: f32 = 3.0; // playback speed of the landing sound, for a chirp
+const SUPPLY_CLAIMED_SOUND_SPEED: f32 = 1.5; // playback speed of the landing sound, for a pickup
+const OTHER_LANDER_VOLUME: f32 = 0.4; // volume of sounds made by landers the player isn't flying
+const DRIFT_TICK_VOLUME: f32 = 0.15;
+const DRIFT_TICK_SPEED: f32 = 4.0; // playback speed of the crash sound, for a click
+
+const AMBIENCE_LOOP_LENGTH: f32 = 4.0; // seconds
+const AMBIENCE_WIND_VOLUME: f32 = 0.15; // at ground level
+const AMBIENCE_WIND_FADE_ALTITUDE: f32 = 500.0; // height above the ground the wind dies out at
+const AMBIENCE_MACHINERY_VOLUME: f32 = 0.1; // right above a base pad
+const AMBIENCE_MACHINERY_RANGE: f32 = 250.0; // distance from a base pad the machinery dies out at
+const AMBIENCE_FADE_RATE: f32 = 0.1; // max volume change per second
+
+const MUSIC_PAD_VOLUME: f32 = 0.15;
+const MUSIC_TENSION_VOLUME: f32 = 0.2; // at full tension
+const MUSIC_TRIUMPH_VOLUME: f32 = 0.2;
+const MUSIC_TENSION_ALTITUDE: f32 = 300.0; // height above the ground tension starts building at
+const MUSIC_TENSION_DESCENT_RATE: f32 = 20.0; // sink rate that counts as fully risky
+const MUSIC_LOW_FUEL_FRACTION: f32 = 0.3; // of a full tank, below which tension builds
+const MUSIC_FADE_RATE: f32 = 0.3; // max volume change per second
+
+const DRIFT_TICK_RATIO: f32 = 0.5; // fraction of the safe drift the tick starts at
+const DRIFT_TICK_MAX_INTERVAL: f32 = 0.6; // seconds between ticks, at DRIFT_TICK_RATIO
+const DRIFT_TICK_MIN_INTERVAL: f32 = 0.1; // seconds between ticks, at the safe drift and beyond
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_message::<AudioCue>()
+        .add_systems(OnEnter(GameState::Game), setup_audio.after(setup_level))
+        .add_systems(
+            Update,
+            drift_tick_system
+                .run_if(drift_tick_enabled)
+                .run_if(in_state(GamePhase::Running)),
+        )
+        .add_systems(
+            Update,
+            (audio_system, ambience_system, music_system).run_if(in_state(GameState::Game)),
+        )
+        .add_systems(OnExit(GameState::Game), cleanup_audio);
+}
+
+#[derive(Resource)]
+pub(super) struct GameSounds {
+    pub(super) thrust_sound: Handle<AudioSource>,
+    pub(super) crash_sound: Handle<AudioSource>,
+    pub(super) landing_sound: Handle<AudioSource>,
+}
+
+/// Ambient loop, faded in and out with where the player is flying.
+#[derive(Component, Clone, Copy)]
+pub(super) enum Ambience {
+    Wind,
+    Machinery,
+}
+
+/// Layer of the in-game music, mixed by how the flight is going.
+#[derive(Component, Clone, Copy)]
+pub(super) enum MusicStem {
+    Pad,
+    Tension,
+    Triumph,
+}
+
+#[derive(Component, PartialEq, Eq)]
+enum GameSound {
+    Thrust,
+    Rcs,
+    Touchdown,
+    Crash,
+    Landing,
+    Warning,
+}
+
+/// Lander a looping sound belongs to.
+#[derive(Component)]
+struct SoundSource(Entity);
+
+/// Gameplay moment that makes a sound, attributed to the lander behind it.
+#[derive(Message, Clone, Copy, Debug)]
+pub(super) enum AudioCue {
+    ThrustStarted(Entity),
+    ThrustStopped(Entity),
+    RcsStarted(Entity),
+    RcsStopped(Entity),
+    Touchdown(Entity),
+    Crash(Entity),
+    Landed(Entity),
+    /// A lander claimed a supply pod.
+    SupplyClaimed(Entity),
+    Warning,
+    DriftTick,
+}
+
+/// Loads the sound effects and starts the ambience and music loops muted, for
+/// their systems to fade in.
+fn setup_audio(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    terrain_seed: Res<TerrainSeed>,
+    mut audio_sources: ResMut<Assets<AudioSource>>,
+) {
+    commands.insert_resource(GameSounds {
+        thrust_sound: asset_server.load("sounds/engine.wav"),
+        crash_sound: asset_server.load("sounds/explosion.wav"),
+        landing_sound: asset_server.load("sounds/win.wav"),
+    });
+
+    let ambience_loops = [
+        (
+            Ambience::Wind,
+            synth::wind(AMBIENCE_LOOP_LENGTH, terrain_seed.0 as u64),
+        ),
+        (Ambience::Machinery, synth::machinery(AMBIENCE_LOOP_LENGTH)),
+    ];
+    for (ambience, samples) in ambience_loops {
+        commands.spawn((
+            DespawnOnExit(GameState::Game),
+            ambience,
+            AudioPlayer::new(audio_sources.add(AudioSource {
+                bytes: synth::wav(&samples).into(),
+            })),
+            PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+        ));
+    }
+
+    // Stems are spawned together and have the same length, so they play in step
+    let music_stems = [
+        (MusicStem::Pad, synth::music_pad()),
+        (MusicStem::Tension, synth::music_tension()),
+        (MusicStem::Triumph, synth::music_triumph()),
+    ];
+    for (stem, samples) in music_stems {
+        commands.spawn((
+            DespawnOnExit(GameState::Game),
+            stem,
+            AudioPlayer::new(audio_sources.add(AudioSource {
+                bytes: synth::wav(&samples).into(),
+            })),
+            PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+        ));
+    }
+}
+
+fn cleanup_audio(mut commands: Commands) {
+    commands.remove_resource::<GameSounds>();
+}
+
+fn audio_system(
+    mut commands: Commands,
+    mut audio_cues: MessageReader<AudioCue>,
+    players: Query<(), With<Player>>,
+    game_sounds: Res<GameSounds>,
+    sounds_query: Query<(Entity, &AudioSink, &GameSound, Option<&SoundSource>)>,
+) {
+    let volume = |source: Entity| {
+        if players.contains(source) {
+            Volume::Linear(1.0)
+        } else {
+            Volume::Linear(OTHER_LANDER_VOLUME)
+        }
+    };
+
+    let stop = |source: Entity, kinds: &[GameSound], commands: &mut Commands| {
+        for (entity, sink, sound, sound_source) in &sounds_query {
+            if sound_source.is_some_and(|sound_source| sound_source.0 == source)
+                && kinds.contains(sound)
+            {
+                sink.stop();
+                commands.entity(entity).despawn();
+            }
+        }
+    };
+
+    for cue in audio_cues.read() {
+        match *cue {
+            AudioCue::ThrustStarted(source) => {
+                commands.spawn((
+                    DespawnOnExit(GamePhase::Running),
+                    GameSound::Thrust,
+                    SoundSource(source),
+                    AudioPlayer::new(game_sounds.thrust_sound.clone()),
+                    PlaybackSettings::LOOP.with_volume(volume(source)),
+                ));
+            }
+            AudioCue::ThrustStopped(source) => {
+                stop(source, &[GameSound::Thrust], &mut commands);
+            }
+            AudioCue::RcsStarted(source) => {
+                commands.spawn((
+                    DespawnOnExit(GamePhase::Running),
+                    GameSound::Rcs,
+                    SoundSource(source),
+                    AudioPlayer::new(game_sounds.thrust_sound.clone()),
+                    PlaybackSettings::LOOP
+                        .with_volume(volume(source) * Volume::Linear(RCS_SOUND_VOLUME))
+                        .with_speed(RCS_SOUND_SPEED),
+                ));
+            }
+            AudioCue::RcsStopped(source) => {
+                stop(source, &[GameSound::Rcs], &mut commands);
+            }
+            AudioCue::Touchdown(source) => {
+                commands.spawn((
+                    DespawnOnExit(GameState::Game),
+                    GameSound::Touchdown,
+                    AudioPlayer::new(game_sounds.crash_sound.clone()),
+                    PlaybackSettings::DESPAWN
+                        .with_volume(volume(source) * Volume::Linear(TOUCHDOWN_SOUND_VOLUME))
+                        .with_speed(TOUCHDOWN_SOUND_SPEED),
+                ));
+            }
+            AudioCue::Crash(source) => {
+                stop(source, &[GameSound::Thrust, GameSound::Rcs], &mut commands);
+                commands.spawn((
+                    DespawnOnExit(GameState::Game),
+                    GameSound::Crash,
+                    AudioPlayer::new(game_sounds.crash_sound.clone()),
+                    PlaybackSettings::DESPAWN.with_volume(volume(source)),
+                ));
+            }
+            AudioCue::Landed(source) => {
+                stop(source, &[GameSound::Thrust, GameSound::Rcs], &mut commands);
+                commands.spawn((
+                    DespawnOnExit(GameState::Game),
+                    GameSound::Landing,
+                    AudioPlayer::new(game_sounds.landing_sound.clone()),
+                    PlaybackSettings::DESPAWN.with_volume(volume(source)),
+                ));
+            }
+            AudioCue::SupplyClaimed(source) => {
+                commands.spawn((
+                    DespawnOnExit(GameState::Game),
+                    GameSound::Landing,
+                    AudioPlayer::new(game_sounds.landing_sound.clone()),
+                    PlaybackSettings::DESPAWN
+                        .with_volume(volume(source))
+                        .with_speed(SUPPLY_CLAIMED_SOUND_SPEED),
+                ));
+            }
+            AudioCue::Warning => {
+                commands.spawn((
+                    DespawnOnExit(GameState::Game),
+                    GameSound::Warning,
+                    AudioPlayer::new(game_sounds.landing_sound.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(WARNING_SOUND_SPEED),
+                ));
+            }
+            AudioCue::DriftTick => {
+                commands.spawn((
+                    DespawnOnExit(GameState::Game),
+                    GameSound::Warning,
+                    AudioPlayer::new(game_sounds.crash_sound.clone()),
+                    PlaybackSettings::DESPAWN
+                        .with_volume(Volume::Linear(DRIFT_TICK_VOLUME))
+                        .with_speed(DRIFT_TICK_SPEED),
+                ));
+            }
+        }
+    }
+}
+
+/// Cross-fades the ambient loops: wind that dies out with altitude, and machinery
+/// around base and outpost pads.
+fn ambience_system(
+    time: Res<Time>,
+    player: Single<&Transform, With<Player>>,
+    terrain: TerrainQuery,
+    land_pads: Query<(&LandPad, &GlobalTransform)>,
+    mut ambience_query: Query<(&Ambience, &mut AudioSink)>,
+) {
+    let position = player.translation.truncate();
+
+    for (ambience, mut sink) in &mut ambience_query {
+        let target = match ambience {
+            Ambience::Wind => {
+                let altitude = terrain.ground_distance(position);
+                AMBIENCE_WIND_VOLUME
+                    * (1.0 - altitude / AMBIENCE_WIND_FADE_ALTITUDE).clamp(0.0, 1.0)
+            }
+            Ambience::Machinery => {
+                let nearest = land_pads
+                    .iter()
+                    .filter(|(land_pad, _)| land_pad.kind != LandPadKind::Standard)
+                    .map(|(_, transform)| transform.translation().truncate().distance(position))
+                    .fold(f32::INFINITY, f32::min);
+                AMBIENCE_MACHINERY_VOLUME
+                    * (1.0 - nearest / AMBIENCE_MACHINERY_RANGE).clamp(0.0, 1.0)
+            }
+        };
+
+        fade_volume(&mut sink, target, AMBIENCE_FADE_RATE * time.delta_secs());
+    }
+}
+
+/// Moves the volume of `sink` towards `target` by at most `step`.
+fn fade_volume(sink: &mut AudioSink, target: f32, step: f32) {
+    let current = sink.volume().to_linear();
+    let volume = current + (target - current).clamp(-step, step);
+    if volume != current {
+        sink.set_volume(Volume::Linear(volume));
+    }
+}
+
+/// Mixes the music stems: the pad plays throughout, tension swells on low, fast
+/// approaches and when fuel runs short, and triumph takes over on touchdown.
+fn music_system(
+    time: Res<Time>,
+    player: Single<(&Transform, &LinearVelocity, &Fuel, &Grounded, &LandingZone), With<Player>>,
+    terrain: TerrainQuery,
+    game_phase: Res<State<GamePhase>>,
+    mut stems: Query<(&MusicStem, &mut AudioSink)>,
+) {
+    let (transform, velocity, fuel, grounded, landing_zone) = *player;
+
+    let altitude = terrain.ground_distance(transform.translation.truncate());
+    let low = 1.0 - (altitude / MUSIC_TENSION_ALTITUDE).clamp(0.0, 1.0);
+    let fast = (-velocity.y / MUSIC_TENSION_DESCENT_RATE).clamp(0.0, 1.0);
+    let short_on_fuel =
+        1.0 - (fuel.0 as f32 / (MAX_FUEL as f32 * MUSIC_LOW_FUEL_FRACTION)).clamp(0.0, 1.0);
+    let risk = (low * fast).max(short_on_fuel);
+
+    let touched_down = grounded.0 && landing_zone.0.is_some();
+
+    let (pad, tension, triumph) = match game_phase.get() {
+        GamePhase::Win => (MUSIC_PAD_VOLUME, 0.0, MUSIC_TRIUMPH_VOLUME),
+        GamePhase::Lose => (MUSIC_PAD_VOLUME / 2.0, 0.0, 0.0),
+        _ if touched_down => (MUSIC_PAD_VOLUME, 0.0, MUSIC_TRIUMPH_VOLUME),
+        _ => (
+            MUSIC_PAD_VOLUME * (1.0 - risk / 2.0),
+            MUSIC_TENSION_VOLUME * risk,
+            0.0,
+        ),
+    };
+
+    for (stem, mut sink) in &mut stems {
+        let target = match stem {
+            MusicStem::Pad => pad,
+            MusicStem::Tension => tension,
+            MusicStem::Triumph => triumph,
+        };
+        fade_volume(&mut sink, target, MUSIC_FADE_RATE * time.delta_secs());
+    }
+}
+
+fn drift_tick_enabled(rules: Res<GameRules>) -> bool {
+    rules.drift_tick
+}
+
+/// Ticks faster the closer the player's drift gets to the safe bound.
+fn drift_tick_system(
+    time: Res<Time>,
+    player: Single<(&Transform, &LinearVelocity), With<Player>>,
+    terrain: TerrainQuery,
+    rules: Res<GameRules>,
+    mut since_tick: Local<f32>,
+    mut audio_cues: MessageWriter<AudioCue>,
+) {
+    *since_tick += time.delta_secs();
+
+    let Some(ratio) = drift_ratio(player.0.translation, player.1.0, &terrain, &rules) else {
+        return;
+    };
+    if ratio < DRIFT_TICK_RATIO {
+        return;
+    }
+
+    let urgency = ((ratio - DRIFT_TICK_RATIO) / (1.0 - DRIFT_TICK_RATIO)).min(1.0);
+    let interval =
+        DRIFT_TICK_MAX_INTERVAL + (DRIFT_TICK_MIN_INTERVAL - DRIFT_TICK_MAX_INTERVAL) * urgency;
+
+    if *since_tick >= interval {
+        *since_tick = 0.0;
+        audio_cues.write(AudioCue::DriftTick);
+    }
+}
@@ -0,0 +1,230 @@
+//! Autoland controller used by every computer-flown lander.
+//!
+//! The controller is a pure function of the lander state and its target, so the
+//! same commands can drive AI landers or be shown to the player as guidance.
+
+use super::{ControlInput, ROTATION_THRUST};
+
+use avian2d::math::PI;
+use bevy::prelude::*;
+
+const MAX_CRUISE_SPEED: f32 = 40.0;
+const HORIZONTAL_GAIN: f32 = 0.2; // desired horizontal speed per unit of distance to the target
+const BRAKING_FRACTION: f32 = 0.5; // share of the spare deceleration budgeted for the descent profile
+const MIN_DESCENT_RATE: f32 = 2.0;
+const APPROACH_RADIUS: f32 = 8.0; // horizontal distance under which the final descent may start
+const HOLD_CLEARANCE: f32 = 60.0; // height above local ground kept while still travelling
+const HOLD_GAIN: f32 = 0.5;
+const VELOCITY_GAIN: f32 = 1.0;
+const MAX_TILT: f32 = 1.0;
+const FINAL_TILT: f32 = 0.15;
+const FINAL_ALTITUDE: f32 = 30.0;
+const ATTITUDE_GAIN: f32 = 4.0;
+const ATTITUDE_DAMPING: f32 = 2.5;
+const ATTITUDE_TOLERANCE: f32 = 0.35;
+const HORIZONTAL_DEADBAND: f32 = 1.0; // horizontal acceleration worth steering for while falling
+
+const SIMULATION_STEP: f32 = 1.0 / 60.0;
+const SIMULATION_TIME_LIMIT: f32 = 300.0;
+
+/// Snapshot of a lander, as seen by the controller.
+pub(super) struct LanderState {
+    pub(super) position: Vec2,
+    pub(super) velocity: Vec2,
+    /// Rotation around Z, 0.0 when upright.
+    pub(super) angle: f32,
+    pub(super) angular_velocity: f32,
+    /// Acceleration the main engine provides at full throttle.
+    pub(super) max_acceleration: f32,
+    /// Magnitude of the gravitational acceleration.
+    pub(super) gravity: f32,
+    /// Terrain height directly below the lander.
+    pub(super) ground_height: f32,
+}
+
+/// What the controller wants the lander to do this instant.
+pub(super) struct Guidance {
+    /// Attitude the controller is turning towards, 0.0 when upright.
+    pub(super) attitude: f32,
+    pub(super) control: ControlInput,
+}
+
+/// Commands that bring the lander to rest with its center at `target`.
+pub(super) fn autoland(state: &LanderState, target: Vec2) -> ControlInput {
+    autoland_guidance(state, target).control
+}
+
+/// Attitude and commands that bring the lander to rest with its center at
+/// `target`.
+pub(super) fn autoland_guidance(state: &LanderState, target: Vec2) -> Guidance {
+    let height = state.position.y - target.y;
+    let distance = target.x - state.position.x;
+
+    let desired_horizontal =
+        (distance * HORIZONTAL_GAIN).clamp(-MAX_CRUISE_SPEED, MAX_CRUISE_SPEED);
+
+    let travelling = distance.abs() > APPROACH_RADIUS;
+    let clearance = state.position.y - state.ground_height;
+
+    // Brake for the hold clearance too while still travelling, or the descent
+    // runs into the ground short of the pad
+    let braking_height = if travelling {
+        height.min(clearance - HOLD_CLEARANCE)
+    } else {
+        height
+    };
+
+    let spare_acceleration = (state.max_acceleration - state.gravity).max(0.0);
+    let mut desired_vertical =
+        -(2.0 * spare_acceleration * BRAKING_FRACTION * braking_height.max(0.0))
+            .sqrt()
+            .max(MIN_DESCENT_RATE);
+
+    if travelling && clearance < HOLD_CLEARANCE {
+        desired_vertical = desired_vertical.max((HOLD_CLEARANCE - clearance) * HOLD_GAIN);
+    }
+
+    let desired_velocity = Vec2::new(desired_horizontal, desired_vertical);
+
+    let desired_acceleration =
+        (desired_velocity - state.velocity) * VELOCITY_GAIN + Vec2::new(0.0, state.gravity);
+
+    let max_tilt = if height < FINAL_ALTITUDE {
+        FINAL_TILT
+    } else {
+        MAX_TILT
+    };
+
+    guide_acceleration(state, desired_acceleration, max_tilt)
+}
+
+/// Flies `autoland` from `state` kinematically, without the physics engine,
+/// over ground `ground_height(x)` high. Returns how long the lander takes to
+/// touch down within `tolerance` of `target` at no more than `max_speed`, or
+/// `None` if it comes down anywhere else, too fast, or not within the time
+/// limit.
+pub(super) fn autoland_time(
+    mut state: LanderState,
+    target: Vec2,
+    tolerance: f32,
+    max_speed: f32,
+    ground_height: impl Fn(f32) -> f32,
+) -> Option<f32> {
+    // Height of the lander center above the ground when resting on it
+    let resting_height = target.y - ground_height(target.x);
+    let mut time = 0.0;
+
+    while time < SIMULATION_TIME_LIMIT {
+        state.ground_height = ground_height(state.position.x);
+        if state.position.y - state.ground_height <= resting_height {
+            let on_target = (state.position.x - target.x).abs() <= tolerance;
+            return (on_target && state.velocity.length() <= max_speed).then_some(time);
+        }
+
+        let command = autoland(&state, target);
+
+        state.angular_velocity += command.rotation * ROTATION_THRUST * SIMULATION_STEP;
+        state.angle += state.angular_velocity * SIMULATION_STEP;
+
+        let thrust_direction = Vec2::new(-state.angle.sin(), state.angle.cos());
+        let acceleration = thrust_direction * command.throttle * state.max_acceleration
+            - Vec2::new(0.0, state.gravity);
+        state.velocity += acceleration * SIMULATION_STEP;
+        state.position += state.velocity * SIMULATION_STEP;
+
+        time += SIMULATION_STEP;
+    }
+
+    None
+}
+
+/// Commands that point the engine along `desired_acceleration` (gravity already
+/// included) and throttle it to match, never tilting past `max_tilt`.
+pub(super) fn hold_acceleration(
+    state: &LanderState,
+    desired_acceleration: Vec2,
+    max_tilt: f32,
+) -> ControlInput {
+    guide_acceleration(state, desired_acceleration, max_tilt).control
+}
+
+fn guide_acceleration(state: &LanderState, desired_acceleration: Vec2, max_tilt: f32) -> Guidance {
+    // The engine cannot pull the lander down, so while it wants to fall faster
+    // than gravity alone only the horizontal part is steered
+    let desired_acceleration = Vec2::new(desired_acceleration.x, desired_acceleration.y.max(0.0));
+
+    let desired_angle =
+        if desired_acceleration.y > 0.0 || desired_acceleration.x.abs() > HORIZONTAL_DEADBAND {
+            (-desired_acceleration.x)
+                .atan2(desired_acceleration.y)
+                .clamp(-max_tilt, max_tilt)
+        } else {
+            0.0
+        };
+
+    let angle_error = wrap_angle(desired_angle - state.angle);
+
+    let rotation = ((ATTITUDE_GAIN * angle_error - ATTITUDE_DAMPING * state.angular_velocity)
+        / ROTATION_THRUST)
+        .clamp(-1.0, 1.0);
+
+    let thrust_direction = Vec2::new(-state.angle.sin(), state.angle.cos());
+
+    let throttle = if angle_error.abs() < ATTITUDE_TOLERANCE && state.max_acceleration > 0.0 {
+        (desired_acceleration.dot(thrust_direction) / state.max_acceleration).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    Guidance {
+        attitude: desired_angle,
+        control: ControlInput {
+            throttle,
+            rotation,
+            ..default()
+        },
+    }
+}
+
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_state() -> LanderState {
+        LanderState {
+            position: Vec2::new(0.0, 850.0),
+            velocity: Vec2::new(50.0, 0.0),
+            angle: PI / 2.0,
+            angular_velocity: 0.0,
+            max_acceleration: 12000.0 / 1800.0,
+            gravity: 1.62,
+            ground_height: 0.0,
+        }
+    }
+
+    #[test]
+    fn autoland_touches_down_on_a_pad_ahead() {
+        for pad_x in [800.0, 1500.0, 4000.0] {
+            let target = Vec2::new(pad_x, 408.0);
+            let time = autoland_time(spawn_state(), target, 8.0, 10.0, |_| 400.0);
+            assert!(time.is_some_and(|time| time > 0.0), "pad at {pad_x}");
+        }
+    }
+
+    #[test]
+    fn autoland_off_the_pad_sets_no_time() {
+        // A wall short of the pad it cannot climb in time
+        let time = autoland_time(spawn_state(), Vec2::new(1500.0, 408.0), 8.0, 10.0, |x| {
+            if x > 500.0 && x < 1400.0 {
+                840.0
+            } else {
+                400.0
+            }
+        });
+        assert_eq!(time, None);
+    }
+}
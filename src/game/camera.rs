@@ -0,0 +1,408 @@
+//! The camera following the lander, the map view, and culling what neither
+//! can see.
+
+use super::{
+    supply_drop::{DerelictPod, SupplyPod},
+    traffic::TrafficLander,
+    world::{LandPadKind, LandPadLabel, PadBeacon, PadLight},
+    *,
+};
+
+const MAP_INITIAL_ZOOM: f32 = 4.0;
+const MAP_MIN_ZOOM: f32 = 1.0;
+const MAP_MAX_ZOOM: f32 = 40.0;
+const MAP_ZOOM_SPEED: f32 = 1.5; // zoom factor per second while a zoom key is held
+const MAP_PAN_SPEED: f32 = 800.0; // viewport units per second, scaled by zoom
+const WAYPOINT_PICK_RADIUS: f32 = 16.0; // viewport units around the map center
+
+const CULLING_MARGIN: f32 = 200.0; // world units around the camera view kept active
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            camera_follow_system.in_set(FlightSystems::Camera),
+            open_map_system,
+        )
+            .run_if(in_state(GamePhase::Running)),
+    )
+    .add_systems(
+        Update,
+        (map_navigation_system, map_draw_system).run_if(in_state(GamePhase::Map)),
+    )
+    .add_systems(Update, culling_system.run_if(in_state(GameState::Game)))
+    .add_systems(OnEnter(GameState::Game), setup_waypoints)
+    .add_systems(OnExit(GameState::Game), cleanup_waypoints)
+    .add_systems(OnEnter(GamePhase::Map), setup_map_view)
+    .add_systems(OnExit(GamePhase::Map), cleanup_map_view);
+}
+
+/// Camera state to restore when the map view closes, and the phase to go back
+/// to, since the map also opens from the pause dialog.
+#[derive(Resource)]
+struct MapView {
+    camera_translation: Vec3,
+    camera_scale: f32,
+    return_phase: GamePhase,
+}
+
+/// Points marked on the map this run.
+#[derive(Resource, Default)]
+struct Waypoints(Vec<Vec2>);
+
+/// Far off-screen entity, hidden and skipped by cosmetic systems until it comes
+/// back near the view.
+#[derive(Component)]
+pub(super) struct Culled;
+
+/// Entities that get culled while far from the camera.
+type Cullable = Or<(
+    With<LandPadLabel>,
+    With<PadLight>,
+    With<PadBeacon>,
+    With<Wreck>,
+    With<TrafficLander>,
+    With<DerelictPod>,
+)>;
+
+fn camera_follow_system(
+    player: Single<(&Transform, &LinearVelocity), With<Player>>,
+    mut background: Single<&mut Transform, (With<BackgroundSky>, Without<Player>)>,
+    mut camera: Single<
+        (&mut Transform, &Projection),
+        (With<Camera>, Without<Player>, Without<BackgroundSky>),
+    >,
+    rules: Res<GameRules>,
+) {
+    let Projection::Orthographic(perspective) = camera.1 else {
+        return;
+    };
+
+    let center = framed_center(
+        camera.0.translation.truncate(),
+        player.0.translation.truncate(),
+        player.1.0,
+        rules.camera_framing,
+        perspective.area.size(),
+    );
+    camera.0.translation.x = center.x;
+    camera.0.translation.y = center.y;
+
+    background.translation.x = camera.0.translation.x;
+    background.translation.y = camera.0.translation.y;
+}
+
+/// Where the camera centered at `center` moves to keep a lander at `position`,
+/// flying at `velocity`, framed: only as far as needed to keep it inside the
+/// dead zone.
+pub(super) fn framed_center(
+    center: Vec2,
+    position: Vec2,
+    velocity: Vec2,
+    framing: CameraFraming,
+    viewport_size: Vec2,
+) -> Vec2 {
+    // Point the camera keeps within the dead zone around its center
+    let target = position + velocity * framing.look_ahead
+        - Vec2::new(0.0, framing.vertical_bias * viewport_size.y);
+    let half_dead_zone = viewport_size * framing.dead_zone / 2.0;
+
+    let center = center.clamp(target - half_dead_zone, target + half_dead_zone);
+
+    // Never show below the terrain floor or far above the operational zone
+    let lowest = viewport_size.y / 2.0;
+    let highest = (OPERATIONAL_ZONE_ABORT_HEIGHT - viewport_size.y / 2.0).max(lowest);
+
+    Vec2::new(center.x, center.y.clamp(lowest, highest))
+}
+
+fn open_map_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        game_phase.set(GamePhase::Map);
+    }
+}
+
+fn setup_waypoints(mut commands: Commands) {
+    commands.init_resource::<Waypoints>();
+}
+
+fn cleanup_waypoints(mut commands: Commands) {
+    commands.remove_resource::<Waypoints>();
+}
+
+fn setup_map_view(
+    mut commands: Commands,
+    mut transitions: MessageReader<StateTransitionEvent<GamePhase>>,
+    mut camera: Single<(&mut Transform, &mut Projection), With<Camera>>,
+    player: Single<&Transform, (With<Player>, Without<Camera>)>,
+    mut background: Single<&mut Visibility, With<BackgroundSky>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    font: Res<MainFont>,
+) {
+    let Projection::Orthographic(perspective) = camera.1.as_mut() else {
+        return;
+    };
+
+    let return_phase = transitions
+        .read()
+        .filter(|transition| transition.entered == Some(GamePhase::Map))
+        .last()
+        .and_then(|transition| transition.exited.clone())
+        .unwrap_or_default();
+
+    commands.insert_resource(MapView {
+        camera_translation: camera.0.translation,
+        camera_scale: perspective.scale,
+        return_phase,
+    });
+
+    perspective.scale = MAP_INITIAL_ZOOM;
+    camera.0.translation = player.translation.truncate().extend(camera.0.translation.z);
+
+    **background = Visibility::Hidden;
+
+    virtual_time.pause();
+
+    commands.spawn((
+        DespawnOnExit(GamePhase::Map),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        children![(
+            Text::new(
+                "MAP: ARROWS TO PAN, +/- OR WHEEL TO ZOOM, SPACE TO MARK A WAYPOINT, M TO CLOSE"
+            ),
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            font.text_font(TextStyle::HudValue),
+        )],
+    ));
+}
+
+fn cleanup_map_view(
+    mut commands: Commands,
+    mut camera: Single<(&mut Transform, &mut Projection), With<Camera>>,
+    mut background: Single<&mut Visibility, With<BackgroundSky>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    map_view: Res<MapView>,
+) {
+    if let Projection::Orthographic(perspective) = camera.1.as_mut() {
+        perspective.scale = map_view.camera_scale;
+    }
+    camera.0.translation = map_view.camera_translation;
+
+    **background = Visibility::Inherited;
+
+    virtual_time.unpause();
+
+    commands.remove_resource::<MapView>();
+}
+
+fn map_navigation_system(
+    real_time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_scroll: Res<AccumulatedMouseScroll>,
+    mut camera: Single<(&mut Transform, &mut Projection), With<Camera>>,
+    map_view: Res<MapView>,
+    mut waypoints: ResMut<Waypoints>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        game_phase.set(map_view.return_phase.clone());
+        return;
+    }
+
+    let Projection::Orthographic(perspective) = camera.1.as_mut() else {
+        return;
+    };
+
+    // Space marks the map center, or unmarks the waypoint already there
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        let center = camera.0.translation.truncate();
+        let pick_radius = WAYPOINT_PICK_RADIUS * perspective.scale;
+        match waypoints
+            .0
+            .iter()
+            .position(|waypoint| waypoint.distance(center) < pick_radius)
+        {
+            Some(index) => {
+                waypoints.0.remove(index);
+            }
+            None => waypoints.0.push(center),
+        }
+    }
+
+    let dt = real_time.delta_secs();
+
+    let mut zoom = MAP_ZOOM_SPEED.powf(-mouse_scroll.delta.y);
+    if keyboard_input.any_pressed([KeyCode::Equal, KeyCode::NumpadAdd]) {
+        zoom /= MAP_ZOOM_SPEED.powf(dt);
+    }
+    if keyboard_input.any_pressed([KeyCode::Minus, KeyCode::NumpadSubtract]) {
+        zoom *= MAP_ZOOM_SPEED.powf(dt);
+    }
+    perspective.scale = (perspective.scale * zoom).clamp(MAP_MIN_ZOOM, MAP_MAX_ZOOM);
+
+    let mut pan = Vec2::ZERO;
+    if keyboard_input.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        pan.x -= 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        pan.x += 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) {
+        pan.y += 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::ArrowDown, KeyCode::KeyS]) {
+        pan.y -= 1.0;
+    }
+    camera.0.translation += (pan * MAP_PAN_SPEED * perspective.scale * dt).extend(0.0);
+}
+
+fn map_draw_system(
+    mut gizmos: Gizmos,
+    camera: Single<(&Transform, &Projection), With<Camera>>,
+    player: Single<&Transform, (With<Player>, Without<Camera>)>,
+    waypoints: Res<Waypoints>,
+    explored_terrain: Res<ExploredTerrain>,
+    terrain_seed: Res<TerrainSeed>,
+    crash_sites: Res<CrashSites>,
+    supply_pods: Query<&Transform, (With<SupplyPod>, Without<Player>)>,
+) {
+    let scale = match camera.1 {
+        Projection::Orthographic(perspective) => perspective.scale,
+        _ => 1.0,
+    };
+    let marker_size = 8.0 * scale;
+
+    for profile in explored_terrain.profiles.values() {
+        gizmos.linestrip_2d(profile.iter().copied(), Color::WHITE);
+    }
+
+    for (position, kind) in explored_terrain.pads.values() {
+        let color = match kind {
+            LandPadKind::Standard => Color::srgb(0.2, 1.0, 0.2),
+            LandPadKind::Base => Color::srgb(0.2, 0.6, 1.0),
+            LandPadKind::Outpost => Color::srgb(1.0, 0.8, 0.2),
+        };
+        gizmos.line_2d(
+            *position - Vec2::X * marker_size,
+            *position + Vec2::X * marker_size,
+            color,
+        );
+        if *kind == LandPadKind::Outpost {
+            gizmos.circle_2d(*position, marker_size, color);
+        }
+    }
+
+    if let Some(crash_sites) = crash_sites.0.get(&terrain_seed.0) {
+        for crash_site in crash_sites {
+            let position = crash_site.translation.truncate();
+            let color = Color::srgb(1.0, 0.2, 0.2);
+            gizmos.line_2d(
+                position - Vec2::splat(marker_size / 2.0),
+                position + Vec2::splat(marker_size / 2.0),
+                color,
+            );
+            gizmos.line_2d(
+                position + Vec2::new(-marker_size / 2.0, marker_size / 2.0),
+                position + Vec2::new(marker_size / 2.0, -marker_size / 2.0),
+                color,
+            );
+        }
+    }
+
+    for supply_pod in &supply_pods {
+        let position = supply_pod.translation.truncate();
+        let color = Color::srgb(0.3, 1.0, 0.6);
+        gizmos.linestrip_2d(
+            [
+                position + Vec2::Y * marker_size,
+                position + Vec2::X * marker_size,
+                position - Vec2::Y * marker_size,
+                position - Vec2::X * marker_size,
+                position + Vec2::Y * marker_size,
+            ],
+            color,
+        );
+    }
+
+    for waypoint in &waypoints.0 {
+        let color = Color::srgb(1.0, 0.4, 1.0);
+        gizmos.line_2d(*waypoint, *waypoint + Vec2::Y * marker_size * 2.0, color);
+        gizmos.linestrip_2d(
+            [
+                *waypoint + Vec2::Y * marker_size * 2.0,
+                *waypoint + Vec2::new(marker_size, marker_size * 1.5),
+                *waypoint + Vec2::Y * marker_size,
+            ],
+            color,
+        );
+    }
+
+    // Crosshair where Space marks a waypoint
+    let center = camera.0.translation.truncate();
+    gizmos.line_2d(
+        center - Vec2::X * marker_size / 2.0,
+        center + Vec2::X * marker_size / 2.0,
+        Color::WHITE,
+    );
+    gizmos.line_2d(
+        center - Vec2::Y * marker_size / 2.0,
+        center + Vec2::Y * marker_size / 2.0,
+        Color::WHITE,
+    );
+
+    gizmos.circle_2d(player.translation.truncate(), marker_size, Color::WHITE);
+}
+
+fn culling_system(
+    mut commands: Commands,
+    camera: Single<(&Transform, &Projection), With<Camera>>,
+    mut cullables: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&Sprite>,
+            &mut Visibility,
+            Has<Culled>,
+        ),
+        Cullable,
+    >,
+) {
+    let Projection::Orthographic(perspective) = camera.1 else {
+        return;
+    };
+
+    let view = Rect::from_center_size(
+        camera.0.translation.truncate() + perspective.area.center(),
+        perspective.area.size(),
+    )
+    .inflate(CULLING_MARGIN);
+
+    for (entity, transform, sprite, mut visibility, culled) in &mut cullables {
+        // Sized sprites, like the tall pad beacons, stay while any part of them is
+        // near the view
+        let half_extent = sprite
+            .and_then(|sprite| sprite.custom_size)
+            .unwrap_or_default()
+            / 2.0;
+        let offset = (transform.translation().truncate() - view.center()).abs();
+        let in_view = (offset - view.half_size()).cmple(half_extent).all();
+
+        if in_view && culled {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Culled>();
+        } else if !in_view && !culled {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(Culled);
+        }
+    }
+}
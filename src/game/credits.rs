@@ -0,0 +1,52 @@
+//! Credits earned by flying further out from the start, spent on outposts.
+
+use super::*;
+
+const MILESTONE_DISTANCE: f32 = 1000.0;
+const MILESTONE_CREDITS: u32 = 100;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), setup_credits)
+        .add_systems(
+            Update,
+            distance_milestone_system.run_if(in_state(GamePhase::Running)),
+        )
+        .add_systems(OnExit(GameState::Game), cleanup_credits);
+}
+
+/// Currency earned during a run, spent on outposts.
+#[derive(Resource, Default)]
+pub(super) struct Credits(pub(super) u32);
+
+/// Furthest distance from the spawn point reached this run.
+#[derive(Resource, Default)]
+pub(super) struct DistanceMilestones {
+    pub(super) furthest: f32,
+}
+
+fn distance_milestone_system(
+    player: Single<&Transform, With<Player>>,
+    mut milestones: ResMut<DistanceMilestones>,
+    mut credits: ResMut<Credits>,
+) {
+    let distance = player.translation.x.abs();
+    if distance <= milestones.furthest {
+        return;
+    }
+
+    let reached = (distance / MILESTONE_DISTANCE).floor() as u32;
+    let previously_reached = (milestones.furthest / MILESTONE_DISTANCE).floor() as u32;
+    credits.0 += (reached - previously_reached) * MILESTONE_CREDITS;
+
+    milestones.furthest = distance;
+}
+
+fn setup_credits(mut commands: Commands) {
+    commands.init_resource::<Credits>();
+    commands.init_resource::<DistanceMilestones>();
+}
+
+fn cleanup_credits(mut commands: Commands) {
+    commands.remove_resource::<Credits>();
+    commands.remove_resource::<DistanceMilestones>();
+}
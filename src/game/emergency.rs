@@ -0,0 +1,130 @@
+//! Emergency starts: runs that begin with the lander in trouble, for a score
+//! bonus on recovering.
+
+use super::{player::EngineOut, *};
+
+const EMERGENCY_CHANCE: f64 = 0.4; // of a run starting in trouble, with emergency starts on
+const EMERGENCY_CONDITION_CHANCE: f64 = 0.5; // of each condition, in an emergency start
+const EMERGENCY_SEED_SALT: u64 = 0xE3E7; // keeps the scenario rolls apart from other seeded rolls
+const EMERGENCY_TUMBLE_SPEED: f32 = 1.5; // rad/s
+const EMERGENCY_LOW_FUEL: u32 = 300;
+const EMERGENCY_ENGINE_OUT_TIME: f32 = 3.0; // seconds
+const EMERGENCY_ATTITUDE_OFFSET: f32 = 1.0; // radians from the usual spawn attitude
+const EMERGENCY_RECOVERY_BONUS: f32 = 0.25; // score bonus per condition recovered from
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnExit(GameState::Game), cleanup_emergency);
+}
+
+/// Way the lander can start a run in trouble.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum EmergencyCondition {
+    Tumbling,
+    LowFuel,
+    EngineOut,
+    OffNominalAttitude,
+}
+
+impl EmergencyCondition {
+    const ALL: [EmergencyCondition; 4] = [
+        EmergencyCondition::Tumbling,
+        EmergencyCondition::LowFuel,
+        EmergencyCondition::EngineOut,
+        EmergencyCondition::OffNominalAttitude,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            EmergencyCondition::Tumbling => "TUMBLING",
+            EmergencyCondition::LowFuel => "LOW FUEL",
+            EmergencyCondition::EngineOut => "ENGINE OUT",
+            EmergencyCondition::OffNominalAttitude => "OFF-NOMINAL ATTITUDE",
+        }
+    }
+}
+
+/// Degraded state the lander started this run in.
+#[derive(Resource)]
+pub(super) struct Emergency(Vec<EmergencyCondition>);
+
+impl Emergency {
+    pub(super) fn recovery_bonus(&self) -> f32 {
+        self.0.len() as f32 * EMERGENCY_RECOVERY_BONUS
+    }
+
+    pub(super) fn labels(&self) -> String {
+        let labels: Vec<&str> = self.0.iter().map(|condition| condition.label()).collect();
+        labels.join(", ")
+    }
+}
+
+/// Emergency start for the run with `seed`, if it gets one. Seeded, so retries
+/// start in the same trouble.
+fn roll_emergency(seed: u32) -> Option<Emergency> {
+    let mut rng = StdRng::seed_from_u64(seed as u64 ^ EMERGENCY_SEED_SALT);
+
+    if !rng.random_bool(EMERGENCY_CHANCE) {
+        return None;
+    }
+
+    let mut conditions: Vec<EmergencyCondition> = EmergencyCondition::ALL
+        .into_iter()
+        .filter(|_| rng.random_bool(EMERGENCY_CONDITION_CHANCE))
+        .collect();
+    if conditions.is_empty() {
+        let index = rng.random_range(0..EmergencyCondition::ALL.len());
+        conditions.push(EmergencyCondition::ALL[index]);
+    }
+
+    Some(Emergency(conditions))
+}
+
+/// Rolls the emergency start for the run with `seed` and puts `player` in the
+/// trouble it comes with, if any, noting it in `run_start` for scoring.
+pub(super) fn start_emergency(
+    commands: &mut Commands,
+    player: Entity,
+    seed: u32,
+    run_start: &mut RunStart,
+) {
+    let Some(emergency) = roll_emergency(seed) else {
+        return;
+    };
+
+    for condition in &emergency.0 {
+        match condition {
+            EmergencyCondition::Tumbling => {
+                commands
+                    .entity(player)
+                    .insert(AngularVelocity(EMERGENCY_TUMBLE_SPEED));
+                run_start.angular_velocity = EMERGENCY_TUMBLE_SPEED;
+            }
+            EmergencyCondition::LowFuel => {
+                commands.entity(player).insert(Fuel(EMERGENCY_LOW_FUEL));
+                run_start.fuel = EMERGENCY_LOW_FUEL;
+            }
+            EmergencyCondition::EngineOut => {
+                commands
+                    .entity(player)
+                    .insert(EngineOut(Timer::from_seconds(
+                        EMERGENCY_ENGINE_OUT_TIME,
+                        TimerMode::Once,
+                    )));
+                run_start.engine_out = EMERGENCY_ENGINE_OUT_TIME;
+            }
+            EmergencyCondition::OffNominalAttitude => {
+                run_start.angle += EMERGENCY_ATTITUDE_OFFSET;
+                commands.entity(player).insert(Transform {
+                    rotation: Quat::from_rotation_z(run_start.angle),
+                    translation: run_start.position.extend(0.0),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    commands.insert_resource(emergency);
+}
+
+fn cleanup_emergency(mut commands: Commands) {
+    commands.remove_resource::<Emergency>();
+}
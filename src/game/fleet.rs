@@ -0,0 +1,299 @@
+//! Fleet runs: several landers descending one after another, the player
+//! flying one at a time while the others hold their attitude.
+
+use super::{
+    audio::AudioCue,
+    outcome::{
+        MultiplierVoided, fuel_efficiency, landing_accuracy, landing_score, resting_pad,
+        stability_issue,
+    },
+    player::{AbortRecovery, Scrubbed},
+    rival::autopilot_system,
+    world::{LandPad, LandPadKind, TerrainQuery},
+    *,
+};
+
+const FLEET_SIZE: usize = 3;
+const FLEET_SPACING: f32 = 160.0; // horizontal gap between consecutive fleet landers at spawn
+const FLEET_SAS_DESCENT_RATE: f32 = 6.0; // max sink rate unattended landers hold
+const FLEET_SAS_GAIN: f32 = 0.5;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            fleet_sas_system
+                .after(autopilot_system)
+                .in_set(FlightSystems::Assist),
+            (fleet_settle_system, fleet_focus_system)
+                .chain()
+                .in_set(FlightSystems::Landing),
+        )
+            .run_if(in_state(GamePhase::Running).and(fleet_enabled)),
+    );
+}
+
+/// Lander of the fleet, in descent order. The one with the control focus also
+/// has [`Player`], the others hold their attitude on their own.
+#[derive(Component)]
+pub(super) struct FleetLander {
+    pub(super) index: usize,
+    pub(super) settle_timer: Timer,
+}
+
+/// Fleet lander that settled, with the score its landing earned.
+#[derive(Component)]
+pub(super) struct FleetLanded(pub(super) f32);
+
+pub(super) fn fleet_enabled(rules: Res<GameRules>) -> bool {
+    rules.fleet
+}
+
+/// Turns the player into the lead of the fleet and spawns the rest of it
+/// trailing behind.
+pub(super) fn spawn_fleet(
+    commands: &mut Commands,
+    player: Entity,
+    lander_sprite: &LanderSprite,
+    leg_material: SurfaceMaterial,
+    rules: &GameRules,
+) {
+    commands.entity(player).insert(FleetLander {
+        index: 0,
+        settle_timer: Timer::from_seconds(rules.win_tolerances.settle_time, TimerMode::Once),
+    });
+
+    for index in 1..FLEET_SIZE {
+        commands
+            .spawn((
+                DespawnOnExit(GameState::Game),
+                FleetLander {
+                    index,
+                    settle_timer: Timer::from_seconds(
+                        rules.win_tolerances.settle_time,
+                        TimerMode::Once,
+                    ),
+                },
+                Grounded(false),
+                LandingZone(None),
+                DeltaVSpent(0.0),
+                ControlInput::default(),
+                lander_body(leg_material),
+                Sprite::from_atlas_image(
+                    lander_sprite.texture.clone(),
+                    TextureAtlas {
+                        layout: lander_sprite.layout.clone(),
+                        index: 0,
+                    },
+                ),
+                PlayerState::Idle,
+                Fuel(MAX_FUEL),
+                Transform {
+                    rotation: Quat::from_rotation_z(PI / 2.0),
+                    translation: Vec3::new(-FLEET_SPACING * index as f32, SPAWN_HEIGHT, 0.0),
+                    ..Default::default()
+                },
+                LinearVelocity(Vec2::new(INITIAL_HORIZONTAL_SPEED, 0.0)),
+            ))
+            .observe(player_crash_observer)
+            .insert_if(RcsFuel::full(), || rules.realism);
+    }
+}
+
+/// Keeps fleet landers without the control focus upright and caps their sink
+/// rate, unless they are still flying an abort recovery.
+fn fleet_sas_system(
+    mut landers: Query<
+        (
+            &mut ControlInput,
+            &Transform,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Mass,
+            &PlayerState,
+        ),
+        (
+            With<FleetLander>,
+            Without<Player>,
+            Without<FleetLanded>,
+            Without<AbortRecovery>,
+        ),
+    >,
+    terrain: TerrainQuery,
+) {
+    for (mut control, transform, linear_velocity, angular_velocity, mass, state) in &mut landers {
+        if *state == PlayerState::Crashed {
+            continue;
+        }
+
+        let lander_state = autopilot::LanderState {
+            position: transform.translation.truncate(),
+            velocity: linear_velocity.0,
+            angle: transform.rotation.to_euler(EulerRot::XYZ).2,
+            angular_velocity: angular_velocity.0,
+            max_acceleration: THRUST / mass.0,
+            gravity: -GRAVITY.y,
+            ground_height: terrain.height_at(transform.translation.x),
+        };
+
+        let sink_excess = (-FLEET_SAS_DESCENT_RATE - linear_velocity.0.y).max(0.0);
+        let desired_acceleration = if sink_excess > 0.0 {
+            Vec2::new(0.0, -GRAVITY.y + sink_excess * FLEET_SAS_GAIN)
+        } else {
+            Vec2::ZERO
+        };
+
+        *control = autopilot::hold_acceleration(&lander_state, desired_acceleration, 0.0);
+    }
+}
+
+/// Per-lander version of the win timer: a fleet lander that holds still on a
+/// non-servicing pad for the settle time is done and banks its score.
+fn fleet_settle_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut landers: Query<
+        (
+            Entity,
+            &mut FleetLander,
+            &Grounded,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Transform,
+            &PlayerState,
+            &Fuel,
+            &DeltaVSpent,
+            Has<MultiplierVoided>,
+            Has<Scrubbed>,
+        ),
+        Without<FleetLanded>,
+    >,
+    land_pads: Query<(Entity, &LandPad, &GlobalTransform)>,
+    rules: Res<GameRules>,
+    time_passed: Res<TimePassed>,
+    mut audio_cues: MessageWriter<AudioCue>,
+) {
+    for (
+        entity,
+        mut fleet_lander,
+        grounded,
+        velocity,
+        angular_velocity,
+        transform,
+        state,
+        fuel,
+        delta_v_spent,
+        multiplier_voided,
+        scrubbed,
+    ) in &mut landers
+    {
+        let position = transform.translation.truncate();
+        let pad = resting_pad(position, &land_pads);
+
+        if *state == PlayerState::Crashed
+            || scrubbed
+            || pad.is_some_and(|(_, land_pad)| land_pad.kind != LandPadKind::Standard)
+            || stability_issue(
+                grounded.0,
+                velocity.0,
+                angular_velocity.0,
+                transform,
+                &rules.win_tolerances,
+            )
+            .is_some()
+        {
+            fleet_lander.settle_timer.reset();
+            continue;
+        }
+
+        fleet_lander.settle_timer.tick(time.delta());
+        if !fleet_lander.settle_timer.just_finished() {
+            continue;
+        }
+
+        let efficiency = fuel_efficiency(
+            delta_v_spent.0,
+            Vec2::new(INITIAL_HORIZONTAL_SPEED, 0.0),
+            SPAWN_HEIGHT - position.y,
+        );
+        let (accuracy, multiplier) = match pad.and_then(|(pad, _)| land_pads.get(pad).ok()) {
+            Some((_, land_pad, pad_transform)) => (
+                landing_accuracy(position.x, (land_pad, pad_transform)).1,
+                if multiplier_voided {
+                    1.0
+                } else {
+                    land_pad.score_multiplier
+                },
+            ),
+            None => (0.0, 1.0),
+        };
+
+        let score = landing_score(
+            time_passed.0,
+            fuel.0,
+            position.y,
+            efficiency,
+            accuracy,
+            multiplier,
+        );
+
+        commands.entity(entity).insert((
+            FleetLanded(score),
+            ControlInput::default(),
+            LockedAxes::ALL_LOCKED,
+        ));
+        audio_cues.write(AudioCue::Landed(entity));
+    }
+}
+
+/// Moves the control focus on Tab, or when the focused lander is done, and ends
+/// the run once no fleet lander is still flying.
+fn fleet_focus_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    fleet: Query<(
+        Entity,
+        &FleetLander,
+        &PlayerState,
+        Has<FleetLanded>,
+        Has<Player>,
+    )>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    let mut flying: Vec<(usize, Entity)> = fleet
+        .iter()
+        .filter(|(_, _, state, landed, _)| !landed && **state != PlayerState::Crashed)
+        .map(|(entity, fleet_lander, ..)| (fleet_lander.index, entity))
+        .collect();
+    flying.sort_by_key(|(index, _)| *index);
+
+    if flying.is_empty() {
+        if fleet.iter().any(|(_, _, _, landed, _)| landed) {
+            game_phase.set(GamePhase::Win);
+        } else {
+            commands.insert_resource(LoseReason::Crashed);
+            game_phase.set(GamePhase::Lose);
+        }
+        return;
+    }
+
+    let Some((focused, focused_lander, ..)) = fleet.iter().find(|(.., focused)| *focused) else {
+        return;
+    };
+
+    let focused_flying = flying.iter().any(|(_, entity)| *entity == focused);
+    if focused_flying && !keyboard_input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    let next = flying
+        .iter()
+        .find(|(index, _)| *index > focused_lander.index)
+        .unwrap_or(&flying[0])
+        .1;
+
+    if next != focused {
+        commands.entity(focused).remove::<Player>();
+        commands.entity(next).insert(Player);
+    }
+}
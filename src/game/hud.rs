@@ -0,0 +1,512 @@
+//! Flight readouts, warnings, and overlays drawn over the run.
+
+use super::{
+    credits::Credits,
+    emergency::Emergency,
+    fleet::{FleetLanded, FleetLander},
+    outcome::{format_time, on_base_pad, stability_issue},
+    outposts::OUTPOST_COST,
+    player::{AbortRecovery, EngineOut, FuelLeak, Scrubbed, TippedOver},
+    rival::landing_target,
+    servicing::Servicing,
+    supply_drop::SupplyPod,
+    world::{LandPad, TerrainQuery},
+    *,
+};
+
+const GUIDANCE_RADIUS: f32 = 24.0; // of the attitude ladder drawn around the lander
+const GUIDANCE_COLOR: Color = Color::srgb(1.0, 0.3, 1.0);
+const GUIDANCE_DIM_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.25);
+
+const HEATMAP_MIN_ALPHA: f32 = 0.2; // of cells with a single run, next to the busiest cell on the seed
+
+const DRIFT_INDICATOR_ALTITUDE: f32 = 120.0; // height above the ground the drift indicator shows up at
+
+const EMERGENCY_ANNOUNCE_TIME: f32 = 6.0; // seconds the HUD announces the emergency for
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), setup_hud)
+        .add_systems(
+            Update,
+            guidance_system
+                .run_if(guidance_enabled)
+                .run_if(in_state(GamePhase::Running)),
+        )
+        .add_systems(
+            Update,
+            (
+                heatmap_overlay_system.run_if(heatmap_overlay_enabled),
+                hud_system,
+            )
+                .run_if(in_state(GameState::Game)),
+        );
+}
+
+#[derive(Component)]
+pub(super) enum HudText {
+    Fuel,
+    XVelocity,
+    YVelocity,
+    TimePassed,
+    Warning,
+    Servicing,
+    Credits,
+    SupplyPod,
+    Stability,
+    Drift,
+    Fleet,
+    Practice,
+}
+
+fn setup_hud(mut commands: Commands, font: Res<MainFont>) {
+    commands.spawn((
+        DespawnOnExit(GameState::Game),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            column_gap: Val::Px(5.0),
+            ..Default::default()
+        },
+        children![
+            (
+                HudText::TimePassed,
+                Text::new("TIME PASSED: 0.0 s"),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::Fuel,
+                Text::new("FUEL: 100"),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::Credits,
+                Text::new("CREDITS: 0"),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::SupplyPod,
+                Text::new(""),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::XVelocity,
+                Text::new("HORIZONTAL VELOCITY: 0.0 m/s"),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::YVelocity,
+                Text::new("VERTICAL VELOCITY: 0.0 m/s"),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::Stability,
+                Text::new(""),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::Drift,
+                Text::new(""),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::Fleet,
+                Text::new(""),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+            (
+                HudText::Practice,
+                Text::new(""),
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Right),
+                font.text_font(TextStyle::HudValue),
+            ),
+        ],
+    ));
+
+    commands.spawn((
+        DespawnOnExit(GameState::Game),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        children![(
+            HudText::Warning,
+            Text::new(""),
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            font.text_font(TextStyle::HudWarning),
+        )],
+    ));
+
+    commands.spawn((
+        DespawnOnExit(GameState::Game),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        children![(
+            HudText::Servicing,
+            Text::new(""),
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            font.text_font(TextStyle::HudStatus),
+        )],
+    ));
+}
+
+fn guidance_enabled(rules: Res<GameRules>) -> bool {
+    rules.guidance
+}
+
+fn heatmap_overlay_enabled(overlay: Res<HeatmapOverlay>) -> bool {
+    overlay.0
+}
+
+/// Outlines the heatmap cells runs on this seed have ended in, redder the more
+/// of them were crashes and brighter the more runs ended there.
+fn heatmap_overlay_system(
+    mut gizmos: Gizmos,
+    heatmap: Res<Heatmap>,
+    terrain_seed: Res<TerrainSeed>,
+) {
+    let Some(cells) = heatmap.cells(terrain_seed.0) else {
+        return;
+    };
+
+    let busiest = cells
+        .values()
+        .map(|cell| cell.landings + cell.crashes)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    for (position, cell) in cells {
+        let runs = cell.landings + cell.crashes;
+        if runs == 0 {
+            continue;
+        }
+
+        let crash_share = cell.crashes as f32 / runs as f32;
+        let intensity =
+            HEATMAP_MIN_ALPHA + (1.0 - HEATMAP_MIN_ALPHA) * runs as f32 / busiest as f32;
+        let center = (position.as_vec2() + 0.5) * HEATMAP_CELL_SIZE;
+
+        gizmos.rect_2d(
+            Isometry2d::from_translation(center),
+            Vec2::splat(HEATMAP_CELL_SIZE),
+            Color::srgba(crash_share, 1.0 - crash_share, 0.0, intensity),
+        );
+    }
+}
+
+/// Draws what the autoland controller would fly towards the nearest pad: a
+/// needle for the attitude it commands next to the lander's own axis, and its
+/// throttle next to the player's.
+fn guidance_system(
+    mut gizmos: Gizmos,
+    player: Single<
+        (
+            &Transform,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Mass,
+            &ControlInput,
+        ),
+        With<Player>,
+    >,
+    land_pads: Query<(&LandPad, &GlobalTransform)>,
+    terrain: TerrainQuery,
+) {
+    let (transform, linear_velocity, angular_velocity, mass, control) = *player;
+    let position = transform.translation.truncate();
+
+    let Some(target) = land_pads.iter().map(landing_target).min_by(|a, b| {
+        (a.x - position.x)
+            .abs()
+            .total_cmp(&(b.x - position.x).abs())
+    }) else {
+        return;
+    };
+
+    let state = autopilot::LanderState {
+        position,
+        velocity: linear_velocity.0,
+        angle: transform.rotation.to_euler(EulerRot::XYZ).2,
+        angular_velocity: angular_velocity.0,
+        max_acceleration: THRUST / mass.0,
+        gravity: -GRAVITY.y,
+        ground_height: terrain.height_at(position.x),
+    };
+
+    let guidance = autopilot::autoland_guidance(&state, target);
+
+    // Attitude ladder: the lander's axis against the commanded one
+    let axis = |angle: f32| Vec2::new(-angle.sin(), angle.cos()) * GUIDANCE_RADIUS;
+    gizmos.circle_2d(position, GUIDANCE_RADIUS, GUIDANCE_DIM_COLOR);
+    gizmos.line_2d(position, position + axis(state.angle), Color::WHITE);
+    gizmos.line_2d(position, position + axis(guidance.attitude), GUIDANCE_COLOR);
+
+    // Throttle bars: the player's on the left, the controller's on the right
+    let bar_base = position + Vec2::new(GUIDANCE_RADIUS + 6.0, -GUIDANCE_RADIUS);
+    for (offset, throttle, color) in [
+        (0.0, control.throttle, Color::WHITE),
+        (4.0, guidance.control.throttle, GUIDANCE_COLOR),
+    ] {
+        let bottom = bar_base + Vec2::new(offset, 0.0);
+        gizmos.line_2d(
+            bottom,
+            bottom + Vec2::new(0.0, 2.0 * GUIDANCE_RADIUS),
+            GUIDANCE_DIM_COLOR,
+        );
+        gizmos.line_2d(
+            bottom,
+            bottom + Vec2::new(0.0, 2.0 * GUIDANCE_RADIUS * throttle),
+            color,
+        );
+    }
+
+    gizmos.circle_2d(target, 4.0, GUIDANCE_COLOR);
+}
+
+fn hud_system(
+    player: Single<
+        (
+            &LinearVelocity,
+            &Fuel,
+            &Transform,
+            Has<FuelLeak>,
+            Option<&Servicing>,
+            &Grounded,
+            &AngularVelocity,
+            &LandingZone,
+            Option<&RcsFuel>,
+            Has<AbortRecovery>,
+            Option<&TippedOver>,
+            Option<&EngineOut>,
+            Has<Scrubbed>,
+        ),
+        With<Player>,
+    >,
+    land_pads: Query<&LandPad>,
+    time_passed: Res<TimePassed>,
+    credits: Res<Credits>,
+    rules: Res<GameRules>,
+    win_timer: Res<WinTimer>,
+    fleet: Query<(&FleetLander, &PlayerState, Has<FleetLanded>, Has<Player>)>,
+    practice_pad: Res<PracticePad>,
+    terrain: TerrainQuery,
+    emergency: Option<Res<Emergency>>,
+    supply_pods: Query<(&SupplyPod, &Transform), Without<Player>>,
+    mut texts_query: Query<(&HudText, &mut Text, &mut TextColor)>,
+) {
+    let focused_fleet_lander = fleet
+        .iter()
+        .find(|(.., focused)| *focused)
+        .map(|(fleet_lander, ..)| fleet_lander);
+
+    // Seconds left to hold still, if the player's lander is settling
+    let settle_remaining = match focused_fleet_lander {
+        Some(fleet_lander) => (fleet_lander.settle_timer.elapsed_secs() > 0.0)
+            .then(|| fleet_lander.settle_timer.remaining_secs()),
+        None => (!win_timer.0.is_paused()).then(|| win_timer.0.remaining_secs()),
+    };
+
+    for (kind, mut text, mut color) in &mut texts_query {
+        match kind {
+            HudText::Credits => {
+                text.0 = format!("CREDITS: {} (O: OUTPOST FOR {})", credits.0, OUTPOST_COST);
+            }
+            HudText::SupplyPod => {
+                text.0 = match supply_pods.iter().next() {
+                    Some((pod, transform)) => {
+                        let offset = transform.translation.x - player.2.translation.x;
+                        let direction = if offset < 0.0 { "LEFT" } else { "RIGHT" };
+                        if pod.landed {
+                            format!(
+                                "SUPPLY POD: {:.0} m {}, LAND BESIDE IT WITHIN {:.0} s",
+                                offset.abs(),
+                                direction,
+                                pod.expiry.remaining_secs()
+                            )
+                        } else {
+                            format!("SUPPLY POD INBOUND: {:.0} m {}", offset.abs(), direction)
+                        }
+                    }
+                    None => String::new(),
+                };
+            }
+            HudText::Fuel => {
+                text.0 = if player.3 {
+                    format!("FUEL: {} (LEAKING)", player.1.0)
+                } else {
+                    format!("FUEL: {}", player.1.0)
+                };
+                if let Some(rcs_fuel) = player.8 {
+                    text.0 += &format!(" RCS: {:.0}%", rcs_fuel.amount / MAX_RCS_FUEL * 100.0);
+                }
+            }
+            HudText::XVelocity => {
+                let horizontal_velocity = player.0.0.x;
+                text.0 = format!("HORIZONTAL VELOCITY: {:.1} m/s", horizontal_velocity);
+            }
+            HudText::YVelocity => {
+                let vertical_velocity = player.0.0.y;
+                text.0 = format!("VERTICAL VELOCITY: {:.1} m/s", vertical_velocity);
+            }
+            HudText::TimePassed => {
+                text.0 = format!("TIME PASSED: {}", format_time(time_passed.0.as_secs_f32()));
+            }
+            HudText::Servicing => {
+                text.0 = match player.4 {
+                    Some(servicing) if player.3 => format!(
+                        "SERVICING: PATCHING LEAK {:.0}%",
+                        servicing.leak_repair * 100.0
+                    ),
+                    Some(_) if player.1.0 < MAX_FUEL => {
+                        format!("SERVICING: REFUELING {}/{}", player.1.0, MAX_FUEL)
+                    }
+                    Some(_) => "SERVICING COMPLETE, LIFT OFF WHEN READY".to_string(),
+                    None => String::new(),
+                };
+            }
+            HudText::Stability => {
+                text.0 = if !player.5.0 || on_base_pad(player.7, &land_pads) {
+                    String::new()
+                } else if let Some(remaining) = settle_remaining {
+                    format!("STABILITY: HOLDING {:.1} s", remaining)
+                } else {
+                    match stability_issue(
+                        player.5.0,
+                        player.0.0,
+                        player.6.0,
+                        player.2,
+                        &rules.win_tolerances,
+                    ) {
+                        Some(issue) => format!("STABILITY: {}", issue),
+                        None => "STABILITY: OK".to_string(),
+                    }
+                };
+            }
+            HudText::Drift => {
+                text.0 = match drift_ratio(player.2.translation, player.0.0, &terrain, &rules) {
+                    Some(ratio) => {
+                        color.0 = drift_color(ratio);
+                        let direction = if player.0.0.x < 0.0 { "LEFT" } else { "RIGHT" };
+                        format!(
+                            "DRIFT: {:.1} m/s {} ({:.0}% OF SAFE)",
+                            player.0.0.x.abs(),
+                            direction,
+                            ratio * 100.0
+                        )
+                    }
+                    None => String::new(),
+                };
+            }
+            HudText::Fleet => {
+                text.0 = match focused_fleet_lander {
+                    Some(fleet_lander) => {
+                        let flying = fleet
+                            .iter()
+                            .filter(|(_, state, landed, _)| {
+                                !landed && **state != PlayerState::Crashed
+                            })
+                            .count();
+                        format!(
+                            "LANDER {} OF {}, {} FLYING (TAB: SWITCH)",
+                            fleet_lander.index + 1,
+                            fleet.iter().count(),
+                            flying
+                        )
+                    }
+                    None => String::new(),
+                };
+            }
+            HudText::Practice => {
+                text.0 = if rules.practice {
+                    format!(
+                        "PRACTICE PAD {} (R: RESET, [ ]: CHANGE PAD)",
+                        practice_pad.0 + 1
+                    )
+                } else {
+                    String::new()
+                };
+            }
+            HudText::Warning => {
+                text.0 = if let Some(tipped_over) = player.10 {
+                    format!(
+                        "TIPPED OVER: ROTATE GENTLY UPRIGHT WITHIN {:.1} s",
+                        tipped_over.window.remaining_secs()
+                    )
+                } else if let Some(engine_out) = player.11 {
+                    format!(
+                        "ENGINE OUT: RELIGHT IN {:.1} s",
+                        engine_out.0.remaining_secs()
+                    )
+                } else if player.12 {
+                    "LANDING SCRUBBED: BOULDER CONTACT, LIFT OFF AND SET DOWN CLEAR OF IT"
+                        .to_string()
+                } else if let Some(emergency) = emergency.as_ref()
+                    && time_passed.0.as_secs_f32() < EMERGENCY_ANNOUNCE_TIME
+                {
+                    format!(
+                        "EMERGENCY START: {} (+{:.0}% IF YOU LAND)",
+                        emergency.labels(),
+                        emergency.recovery_bonus() * 100.0
+                    )
+                } else if player.9 {
+                    "ABORT: AUTOPILOT RECOVERING, PRESS B TO TAKE BACK CONTROL".to_string()
+                } else if player.2.translation.y > OPERATIONAL_ZONE_WARNING_HEIGHT {
+                    "LEAVING OPERATIONAL ZONE".to_string()
+                } else {
+                    String::new()
+                };
+            }
+        }
+    }
+}
+
+/// Sideways speed relative to the safe landing speed, while close enough to the
+/// ground for it to matter.
+pub(super) fn drift_ratio(
+    position: Vec3,
+    velocity: Vec2,
+    terrain: &TerrainQuery,
+    rules: &GameRules,
+) -> Option<f32> {
+    let altitude = terrain.ground_distance(position.truncate());
+    let ratio = velocity.x.abs() / rules.win_tolerances.max_speed;
+    (altitude < DRIFT_INDICATOR_ALTITUDE).then_some(ratio)
+}
+
+/// Green well inside the safe drift, through yellow, to red at and beyond it.
+fn drift_color(ratio: f32) -> Color {
+    let ratio = ratio.clamp(0.0, 1.0);
+    Color::srgb((ratio * 2.0).min(1.0), (2.0 - ratio * 2.0).min(1.0), 0.0)
+}
@@ -0,0 +1,739 @@
+//! How a run ends: landing and crash detection, scoring, and the end screens.
+
+use super::{
+    audio::AudioCue,
+    emergency::Emergency,
+    fleet::{FleetLanded, FleetLander, fleet_enabled},
+    player::{FuelLeak, Scrubbed, TippedOver},
+    rival::{Rival, RivalStatus},
+    world::{Ground, LandPad, LandPadKind, PadObstruction, standard_pads},
+    *,
+};
+
+const FUEL_LEAK_IMPULSE_MAGNITUDE: f32 = 8000.0; // impacts above this puncture the tank
+const MAX_FUEL_LEAK_RATE: f32 = 20.0;
+
+const MAX_TIME_SCORE_BONUS: f32 = 1000.0;
+const TIME_SCORE_BONUS_REDUCTION_FACTOR: f32 = MAX_TIME_SCORE_BONUS / 200.0; // reduces to zero at 200 seconds
+
+const FUEL_SCORE_BONUS_FACTOR: f32 = 2.0; // each unit of fuel left gives this much score
+
+const FUEL_EFFICIENCY_SCORE_BONUS: f32 = 500.0; // awarded in full for a burn matching the theoretical minimum
+
+const ACCURACY_SCORE_BONUS: f32 = 500.0; // awarded in full for touching down on the exact pad center
+
+const MAX_HEIGHT_SCORE_BONUS_FACTOR: f32 = 1.0; // each unit of height at landing gives this much score
+
+const PAR_SILVER_FACTOR: f32 = 1.25; // times par still worth a silver medal
+const PAR_BRONZE_FACTOR: f32 = 1.5; // times par still worth a bronze medal
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            start_win_timer_system,
+            reset_win_timer_system,
+            tick_win_timer_system,
+        )
+            .chain()
+            .run_if(not(fleet_enabled))
+            .in_set(FlightSystems::Landing)
+            .run_if(in_state(GamePhase::Running)),
+    )
+    .add_systems(OnEnter(GamePhase::Lose), setup_lose_screen)
+    .add_systems(OnExit(GamePhase::Lose), cleanup_lose_screen)
+    .add_systems(OnEnter(GamePhase::Win), setup_win_screen)
+    .add_systems(OnExit(GamePhase::Win), cleanup_win_screen);
+}
+
+/// Root of the win or lose screen, hidden while watching the replay.
+#[derive(Component)]
+pub(super) struct EndScreen;
+
+/// The lander aborted this run, so its landing scores without a pad multiplier.
+#[derive(Component)]
+pub(super) struct MultiplierVoided;
+
+/// Landing pad whose landing zone the lander is currently inside.
+#[derive(Component)]
+pub(super) struct LandingZone(pub(super) Option<Entity>);
+
+#[derive(Resource)]
+pub(super) struct WinTimer(pub(super) Timer);
+
+/// Pad the lander was resting on when the win timer completed, and the score
+/// multiplier it was worth at that moment.
+#[derive(Resource)]
+pub(super) struct LandingResult {
+    pub(super) pad: Option<Entity>,
+    pub(super) multiplier: f32,
+}
+
+#[derive(Resource, Clone, Copy)]
+pub(super) enum LoseReason {
+    Crashed,
+    LeftOperationalZone,
+    LostContact,
+    TippedOver,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Medal {
+    Gold,
+    Silver,
+    Bronze,
+}
+
+impl Medal {
+    fn for_time(time: f32, par: f32) -> Option<Medal> {
+        if time <= par {
+            Some(Medal::Gold)
+        } else if time <= par * PAR_SILVER_FACTOR {
+            Some(Medal::Silver)
+        } else if time <= par * PAR_BRONZE_FACTOR {
+            Some(Medal::Bronze)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Medal::Gold => "Gold",
+            Medal::Silver => "Silver",
+            Medal::Bronze => "Bronze",
+        }
+    }
+}
+
+pub(super) fn player_entered_landing_zone(
+    event: On<CollisionStart>,
+    landpads: Query<&LandPad>,
+    mut landing_zones: Query<(&mut LandingZone, Has<Rival>)>,
+    mut run_events: MessageWriter<RunEvent>,
+) {
+    let this_entity = event.collider1;
+    let other_entity = event.collider2;
+
+    let Ok(land_pad) = landpads.get(this_entity) else {
+        return;
+    };
+
+    let Ok((mut landing_zone, rival)) = landing_zones.get_mut(other_entity) else {
+        return;
+    };
+
+    landing_zone.0 = Some(this_entity);
+
+    // The run log follows the player's landers, not the rival's
+    if rival {
+        return;
+    }
+
+    run_events.write(RunEvent::PadEntered {
+        multiplier: land_pad.score_multiplier,
+    });
+}
+
+pub(super) fn player_exited_landing_zone(
+    event: On<CollisionEnd>,
+    mut landing_zones: Query<&mut LandingZone>,
+) {
+    let other_entity = event.collider2;
+
+    if let Ok(mut landing_zone) = landing_zones.get_mut(other_entity) {
+        landing_zone.0 = None;
+    }
+}
+
+pub(super) fn player_crash_observer(
+    event: On<CollisionStart>,
+    mut commands: Commands,
+    mut landers: Query<&mut PlayerState, Or<(With<Player>, With<FleetLander>)>>,
+    ground_query: Query<Entity, With<Ground>>,
+    obstructions: Query<(), With<PadObstruction>>,
+    leaks: Query<(), With<FuelLeak>>,
+    collisions: Collisions,
+    rules: Res<GameRules>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+    mut run_events: MessageWriter<RunEvent>,
+    mut audio_cues: MessageWriter<AudioCue>,
+) {
+    let (a, b) = (event.collider1, event.collider2);
+
+    let player_entity = if landers.contains(a) {
+        a
+    } else if landers.contains(b) {
+        b
+    } else {
+        return;
+    };
+
+    let other_entity = if player_entity == a { b } else { a };
+
+    if ground_query.get(other_entity).is_err() {
+        return;
+    }
+
+    if obstructions.contains(other_entity) {
+        commands.entity(player_entity).insert(Scrubbed);
+    }
+
+    let mut impact_impulse_magnitude = 0.0;
+    for contact_pair in collisions.collisions_with(player_entity) {
+        impact_impulse_magnitude += contact_pair.total_normal_impulse_magnitude();
+    }
+
+    run_events.write(RunEvent::Touchdown {
+        impulse: impact_impulse_magnitude,
+    });
+
+    audio_cues.write(
+        if impact_impulse_magnitude > SAFE_LANDING_IMPULSE_MAGNITUDE {
+            AudioCue::Crash(player_entity)
+        } else {
+            AudioCue::Touchdown(player_entity)
+        },
+    );
+
+    if impact_impulse_magnitude > SAFE_LANDING_IMPULSE_MAGNITUDE && rules.fleet {
+        // The rest of the fleet keeps flying, fleet_focus_system ends the run
+        if let Ok(mut state) = landers.get_mut(player_entity) {
+            *state = PlayerState::Crashed;
+        }
+        commands
+            .entity(player_entity)
+            .insert((ControlInput::default(), LockedAxes::ALL_LOCKED));
+    } else if impact_impulse_magnitude > SAFE_LANDING_IMPULSE_MAGNITUDE {
+        commands.insert_resource(LoseReason::Crashed);
+        game_phase.set(GamePhase::Lose);
+    } else if impact_impulse_magnitude > FUEL_LEAK_IMPULSE_MAGNITUDE
+        && !leaks.contains(player_entity)
+    {
+        let severity = (impact_impulse_magnitude - FUEL_LEAK_IMPULSE_MAGNITUDE)
+            / (SAFE_LANDING_IMPULSE_MAGNITUDE - FUEL_LEAK_IMPULSE_MAGNITUDE);
+
+        commands.entity(player_entity).insert(FuelLeak {
+            rate: MIN_FUEL_LEAK_RATE + (MAX_FUEL_LEAK_RATE - MIN_FUEL_LEAK_RATE) * severity,
+            lost: 0.0,
+            particle_timer: Timer::from_seconds(LEAK_PARTICLE_INTERVAL, TimerMode::Repeating),
+        });
+    }
+}
+
+fn tick_win_timer_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    player: Single<(&Transform, Has<MultiplierVoided>), With<Player>>,
+    land_pads: Query<(Entity, &LandPad, &GlobalTransform)>,
+    mut win_timer: ResMut<WinTimer>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    win_timer.0.tick(time.delta());
+    if win_timer.0.just_finished() {
+        let pad = resting_pad(player.0.translation.truncate(), &land_pads);
+        commands.insert_resource(LandingResult {
+            pad: pad.map(|(entity, _)| entity),
+            multiplier: match pad {
+                Some((_, land_pad)) if !player.1 => land_pad.score_multiplier,
+                _ => 1.0,
+            },
+        });
+        game_phase.set(GamePhase::Win);
+    }
+}
+
+/// Pad whose surface the lander at `position` is standing on, found from the pad
+/// geometry rather than the sensor overlap, which flickers while settling.
+pub(super) fn resting_pad<'a>(
+    position: Vec2,
+    land_pads: &'a Query<(Entity, &LandPad, &GlobalTransform)>,
+) -> Option<(Entity, &'a LandPad)> {
+    land_pads
+        .iter()
+        .find(|(_, land_pad, pad_transform)| {
+            let pad_center = pad_transform.translation().truncate();
+            let pad_surface = pad_center.y - LAND_PAD_SENSOR_HEIGHT / 2.0;
+            (position.x - pad_center.x).abs() <= land_pad.width / 2.0
+                && (position.y - pad_surface).abs() <= LANDER_SIZE.y as f32
+        })
+        .map(|(entity, land_pad, _)| (entity, land_pad))
+}
+
+/// Whether the lander is over a pad that services it, a base or an outpost.
+pub(super) fn on_base_pad(landing_zone: &LandingZone, land_pads: &Query<&LandPad>) -> bool {
+    landing_zone
+        .0
+        .and_then(|pad| land_pads.get(pad).ok())
+        .is_some_and(|land_pad| land_pad.kind != LandPadKind::Standard)
+}
+
+/// First condition keeping a lander from counting as settled, if any.
+pub(super) fn stability_issue(
+    grounded: bool,
+    velocity: Vec2,
+    angular_velocity: f32,
+    transform: &Transform,
+    tolerances: &WinTolerances,
+) -> Option<String> {
+    let tilt = transform.rotation.to_euler(EulerRot::XYZ).2.abs();
+
+    if !grounded {
+        Some("AIRBORNE".to_string())
+    } else if velocity.length() >= tolerances.max_speed {
+        Some(format!(
+            "MOVING {:.1} m/s (MAX {:.1})",
+            velocity.length(),
+            tolerances.max_speed
+        ))
+    } else if angular_velocity.abs() >= tolerances.max_angular_speed {
+        Some(format!(
+            "SPINNING {:.2} rad/s (MAX {:.2})",
+            angular_velocity.abs(),
+            tolerances.max_angular_speed
+        ))
+    } else if tilt >= tolerances.max_tilt {
+        Some(format!(
+            "TILTED {:.0} DEG (MAX {:.0})",
+            tilt.to_degrees(),
+            tolerances.max_tilt.to_degrees()
+        ))
+    } else {
+        None
+    }
+}
+
+fn start_win_timer_system(
+    player: Single<
+        (
+            &Grounded,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Transform,
+            &LandingZone,
+            Has<Scrubbed>,
+        ),
+        With<Player>,
+    >,
+    land_pads: Query<&LandPad>,
+    rules: Res<GameRules>,
+    mut win_timer: ResMut<WinTimer>,
+) {
+    if win_timer.0.is_paused()
+        && !player.5
+        && !on_base_pad(player.4, &land_pads)
+        && stability_issue(
+            player.0.0,
+            player.1.0,
+            player.2.0,
+            player.3,
+            &rules.win_tolerances,
+        )
+        .is_none()
+    {
+        win_timer.0.reset();
+        win_timer.0.unpause();
+    }
+}
+
+fn reset_win_timer_system(
+    player: Single<
+        (
+            &Grounded,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Transform,
+            &LandingZone,
+            Has<Scrubbed>,
+        ),
+        With<Player>,
+    >,
+    land_pads: Query<&LandPad>,
+    rules: Res<GameRules>,
+    mut win_timer: ResMut<WinTimer>,
+) {
+    if !win_timer.0.is_paused()
+        && (player.5
+            || on_base_pad(player.4, &land_pads)
+            || stability_issue(
+                player.0.0,
+                player.1.0,
+                player.2.0,
+                player.3,
+                &rules.win_tolerances,
+            )
+            .is_some())
+    {
+        win_timer.0.pause();
+    }
+}
+
+/// Smallest velocity change that can bring a lander spawned at `spawn_velocity`
+/// to rest `drop_height` below the spawn point: kill the horizontal speed and the
+/// vertical speed it has after falling that far, both as instant burns.
+fn minimum_delta_v(spawn_velocity: Vec2, drop_height: f32) -> f32 {
+    spawn_velocity.x.abs()
+        + (spawn_velocity.y.powi(2) + 2.0 * -GRAVITY.y * drop_height.max(0.0)).sqrt()
+}
+
+pub(super) fn landing_score(
+    time_passed: Duration,
+    fuel: u32,
+    height: f32,
+    efficiency: f32,
+    accuracy: f32,
+    multiplier: f32,
+) -> f32 {
+    ((MAX_TIME_SCORE_BONUS - time_passed.as_secs_f32() * TIME_SCORE_BONUS_REDUCTION_FACTOR)
+        + (fuel as f32 * FUEL_SCORE_BONUS_FACTOR)
+        + (height * MAX_HEIGHT_SCORE_BONUS_FACTOR)
+        + (efficiency * FUEL_EFFICIENCY_SCORE_BONUS)
+        + (accuracy * ACCURACY_SCORE_BONUS))
+        * multiplier
+}
+
+pub(super) fn fuel_efficiency(delta_v_spent: f32, spawn_velocity: Vec2, drop_height: f32) -> f32 {
+    if delta_v_spent <= 0.0 {
+        return 1.0;
+    }
+
+    (minimum_delta_v(spawn_velocity, drop_height) / delta_v_spent).min(1.0)
+}
+
+/// Horizontal distance between the lander and the middle of the pad's clear
+/// stretch, and the accuracy factor it earns: 1.0 on the middle, 0.0 at the
+/// pad edge or the boulder.
+pub(super) fn landing_accuracy(
+    lander_x: f32,
+    (land_pad, pad_transform): (&LandPad, &GlobalTransform),
+) -> (f32, f32) {
+    let clear_center = pad_transform.translation().x + land_pad.clear_offset;
+    let offset = (lander_x - clear_center).abs();
+    let accuracy = (1.0 - offset / (land_pad.clear_width / 2.0)).clamp(0.0, 1.0);
+    (offset, accuracy)
+}
+
+/// Reference time for a run: how long the autopilot takes to fly from `start`
+/// down onto the `pad`-th standard pad and settle there, if it gets down on it
+/// at all.
+pub(super) fn par_time(
+    terrain_generator: &TerrainGenerator,
+    pad: usize,
+    start: &RunStart,
+    tolerances: &WinTolerances,
+) -> Option<f32> {
+    let (pad_surface, pad) = standard_pads(terrain_generator).nth(pad)?;
+    let pad_width = pad.width as f32;
+    let (clear_offset, clear_width) = pad.hazard.map_or((0.0, pad_width), |hazard| {
+        (
+            hazard.clear_offset(pad_width),
+            hazard.clear_width(pad_width),
+        )
+    });
+
+    // Coasts until a dead engine relights, then hands over to the autopilot
+    let coast = start.engine_out;
+    let state = autopilot::LanderState {
+        position: start.position + start.velocity * coast + GRAVITY * coast.powi(2) / 2.0,
+        velocity: start.velocity + GRAVITY * coast,
+        angle: start.angle + start.angular_velocity * coast,
+        angular_velocity: start.angular_velocity,
+        max_acceleration: THRUST / (DRY_LANDER_MASS + start.fuel as f32 * FUEL_MASS_FACTOR),
+        gravity: -GRAVITY.y,
+        ground_height: 0.0,
+    };
+    let target = pad_surface + Vec2::new(clear_offset, LANDER_SIZE.y as f32 / 2.0);
+
+    let flight_time = autopilot::autoland_time(
+        state,
+        target,
+        clear_width / 2.0,
+        tolerances.max_speed,
+        |x| {
+            if (x - pad_surface.x).abs() <= pad_width / 2.0 {
+                pad_surface.y
+            } else {
+                // Chunks are drawn half a chunk to the right of the terrain they sample.
+                terrain_generator.sample_height(x - CHUNK_WIDTH / 2.0)
+            }
+        },
+    )?;
+
+    Some(coast + flight_time + tolerances.settle_time)
+}
+
+pub(super) fn format_time(seconds: f32) -> String {
+    let total_secs = seconds as u64;
+    format!("{:02}:{:02}", total_secs / 60, total_secs % 60)
+}
+
+fn setup_lose_screen(
+    mut commands: Commands,
+    mut player: Single<
+        (
+            Entity,
+            &mut PlayerState,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            &Transform,
+        ),
+        With<Player>,
+    >,
+    font: Res<MainFont>,
+    terrain_seed: Res<TerrainSeed>,
+    mut crash_sites: ResMut<CrashSites>,
+    mut audio_cues: MessageWriter<AudioCue>,
+    lose_reason: Option<Res<LoseReason>>,
+    rules: Res<GameRules>,
+    time_passed: Res<TimePassed>,
+    flight_recorder: Res<FlightRecorder>,
+    mut runs_finished: MessageWriter<RunFinished>,
+) {
+    let lose_reason = lose_reason.map_or(LoseReason::Crashed, |reason| *reason);
+
+    runs_finished.write(RunFinished {
+        outcome: match lose_reason {
+            LoseReason::Crashed | LoseReason::LostContact | LoseReason::TippedOver => {
+                RunOutcome::Crashed
+            }
+            LoseReason::LeftOperationalZone => RunOutcome::Aborted,
+        },
+        duration: time_passed.0,
+        distance: flight_recorder.distance,
+        score: None,
+        seed: terrain_seed.0,
+        position: player.4.translation.truncate(),
+        practice: rules.practice,
+    });
+
+    if let LoseReason::Crashed = lose_reason {
+        crash_sites
+            .0
+            .entry(terrain_seed.0)
+            .or_default()
+            .push(*player.4);
+    } else {
+        // Crashes already made their sound on impact
+        audio_cues.write(AudioCue::Crash(player.0));
+    }
+
+    *player.1 = PlayerState::Crashed;
+    player.2.0 = Vec2::ZERO;
+    player.3.0 = 0.0;
+    commands.entity(player.0).insert(LockedAxes::ALL_LOCKED);
+
+    commands.spawn((
+        DespawnOnExit(GamePhase::Lose),
+        EndScreen,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        children![(
+            Text::new(format!(
+                "{}\nPress SPACE to return to menu.\nPress R to retry this terrain.\nPress V to watch the replay.\nPress F6 to export the telemetry.",
+                match lose_reason {
+                    LoseReason::Crashed => "You Lost!",
+                    LoseReason::LeftOperationalZone => "Mission Aborted: you left the operational zone.",
+                    LoseReason::LostContact => "Mission Aborted: contact with the lander was lost.",
+                    LoseReason::TippedOver => "You Lost! The lander tipped over and stayed down.",
+                }
+            )),
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            font.text_font(TextStyle::Title),
+            TextBackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        )],
+    ));
+}
+
+fn cleanup_lose_screen(mut _commands: Commands) {}
+
+fn setup_win_screen(
+    mut commands: Commands,
+    player: Single<(&Fuel, &Transform, &DeltaVSpent, Entity), With<Player>>,
+    land_pads: Query<(&LandPad, &GlobalTransform)>,
+    fleet: Query<(&FleetLander, Option<&FleetLanded>)>,
+    terrain_seed: Res<TerrainSeed>,
+    run_start: Res<RunStart>,
+    landing_result: Option<Res<LandingResult>>,
+    rules: Res<GameRules>,
+    time_passed: Res<TimePassed>,
+    rival_status: Option<Res<RivalStatus>>,
+    flight_recorder: Res<FlightRecorder>,
+    mut runs_finished: MessageWriter<RunFinished>,
+    font: Res<MainFont>,
+    mut audio_cues: MessageWriter<AudioCue>,
+    emergency: Option<Res<Emergency>>,
+    lifetime_stats: Res<LifetimeStats>,
+) {
+    let efficiency = fuel_efficiency(
+        player.2.0,
+        run_start.velocity,
+        run_start.position.y - player.1.translation.y,
+    );
+
+    let multiplier = landing_result
+        .as_ref()
+        .map_or(1.0, |landing_result| landing_result.multiplier);
+
+    let (accuracy_text, accuracy) = match landing_result
+        .as_ref()
+        .and_then(|landing_result| landing_result.pad)
+        .and_then(|pad| land_pads.get(pad).ok())
+    {
+        Some((land_pad, pad_transform)) => {
+            let (offset, accuracy) =
+                landing_accuracy(player.1.translation.x, (land_pad, pad_transform));
+            (format!("{:.1} m from center", offset), accuracy)
+        }
+        None => ("Off pad".to_string(), 0.0),
+    };
+
+    let run_time = time_passed.0.as_secs_f32();
+    let par_text = match run_start.par {
+        Some(par) => match Medal::for_time(run_time, par) {
+            Some(Medal::Gold) => format!(
+                "Time: {} (par {}) - Gold, you beat the autopilot!",
+                format_time(run_time),
+                format_time(par)
+            ),
+            Some(medal) => format!(
+                "Time: {} (par {}) - {}",
+                format_time(run_time),
+                format_time(par),
+                medal.name()
+            ),
+            None => format!("Time: {} (par {})", format_time(run_time), format_time(par)),
+        },
+        None => format!("Time: {}", format_time(run_time)),
+    };
+
+    let rival_text = match rival_status.as_deref() {
+        Some(RivalStatus::Landed(rival_time)) if *rival_time < time_passed.0 => format!(
+            "\nThe rival landed first, {:.1} s ahead of you.",
+            (time_passed.0 - *rival_time).as_secs_f32()
+        ),
+        Some(RivalStatus::Landed(_)) | Some(RivalStatus::Crashed) => {
+            "\nYou beat the rival!".to_string()
+        }
+        Some(RivalStatus::Flying) => "\nThe rival is still airborne.".to_string(),
+        None => String::new(),
+    };
+
+    // Lifetime stats only count this landing once the run is recorded, and never
+    // count practice runs
+    let unlock_text = if !rules.practice && lifetime_stats.landings + 1 == GIMBAL_UNLOCK_LANDINGS {
+        "\nEngine gimbal unlocked! Hold SHIFT with LEFT/RIGHT to tilt the thrust.".to_string()
+    } else {
+        String::new()
+    };
+
+    let recovery_bonus = emergency
+        .as_ref()
+        .map_or(0.0, |emergency| emergency.recovery_bonus());
+
+    let score = landing_score(
+        time_passed.0,
+        player.0.0,
+        player.1.translation.y,
+        efficiency,
+        accuracy,
+        multiplier,
+    ) * (1.0 + recovery_bonus);
+
+    let recovery_text = match emergency.as_ref() {
+        Some(emergency) => format!(
+            "Recovered from {}: +{:.0}%\n",
+            emergency.labels().to_lowercase(),
+            recovery_bonus * 100.0
+        ),
+        None => String::new(),
+    };
+
+    let mut fleet_scores: Vec<(usize, Option<f32>)> = fleet
+        .iter()
+        .map(|(fleet_lander, landed)| (fleet_lander.index, landed.map(|landed| landed.0)))
+        .collect();
+    fleet_scores.sort_by_key(|(index, _)| *index);
+
+    // Fleet runs score the sum of every landing instead
+    let (score, summary_text) = if fleet_scores.is_empty() {
+        (
+            score,
+            format!(
+                "Score: {:.2} (x{:.1})\n{}Fuel efficiency: {:.0}%\nAccuracy: {}\n{}",
+                score,
+                multiplier,
+                recovery_text,
+                efficiency * 100.0,
+                accuracy_text,
+                par_text
+            ),
+        )
+    } else {
+        let total: f32 = fleet_scores.iter().filter_map(|(_, score)| *score).sum();
+        let lines: Vec<String> = fleet_scores
+            .iter()
+            .map(|(index, score)| match score {
+                Some(score) => format!("Lander {}: {:.2}", index + 1, score),
+                None => format!("Lander {}: crashed", index + 1),
+            })
+            .collect();
+        (
+            total,
+            format!(
+                "Fleet score: {:.2}\n{}\nTime: {}",
+                total,
+                lines.join("\n"),
+                format_time(run_time)
+            ),
+        )
+    };
+
+    runs_finished.write(RunFinished {
+        outcome: RunOutcome::Landed,
+        duration: time_passed.0,
+        distance: flight_recorder.distance,
+        score: Some(score),
+        seed: terrain_seed.0,
+        position: player.1.translation.truncate(),
+        practice: rules.practice,
+    });
+
+    commands.spawn((
+        DespawnOnExit(GamePhase::Win),
+        EndScreen,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        children![(
+            Text::new(format!(
+                "You Landed Successfully!\nPress SPACE to return to menu.\nPress R to retry this terrain.\nPress V to watch the replay.\nPress F6 to export the telemetry.\n{}{}{}",
+                summary_text, rival_text, unlock_text
+            )),
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            font.text_font(TextStyle::Title),
+            TextBackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        )],
+    ));
+
+    // Fleet landers made their sound as each of them settled
+    if fleet.is_empty() {
+        audio_cues.write(AudioCue::Landed(player.3));
+    }
+}
+
+fn cleanup_win_screen(mut _commands: Commands) {}
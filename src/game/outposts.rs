@@ -0,0 +1,239 @@
+//! Outposts bought on standard pads, and hops between them planned on the
+//! map view.
+
+use super::{
+    credits::Credits,
+    servicing::Servicing,
+    world::{LandPad, LandPadKind, LandPadLabel},
+    *,
+};
+
+pub(super) const OUTPOST_COST: u32 = 200;
+const HOP_FUEL_PER_UNIT: f32 = 0.05;
+const HOP_SPEED: f32 = 100.0; // simulated transit speed, for the time penalty
+const HOP_APPROACH_HEIGHT: f32 = 150.0;
+const HOP_APPROACH_SPEED: f32 = 5.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), setup_outposts)
+        .add_systems(
+            Update,
+            (
+                establish_outpost_system.run_if(in_state(GamePhase::Running)),
+                hop_planner_system
+                    .run_if(in_state(GamePhase::Map).and(resource_exists::<HopPlanner>)),
+            ),
+        )
+        .add_systems(OnEnter(GamePhase::Map), setup_hop_planner)
+        .add_systems(OnExit(GamePhase::Map), cleanup_hop_planner)
+        .add_systems(OnExit(GameState::Game), cleanup_outposts);
+}
+
+/// Outposts established this run: chunk origin to pad surface center.
+#[derive(Resource, Default)]
+pub(super) struct Outposts(pub(super) HashMap<i32, Vec2>);
+
+/// Outposts reachable with a hop, planned on the map view while docked at an outpost.
+#[derive(Resource)]
+struct HopPlanner {
+    destinations: Vec<Vec2>,
+    selected: usize,
+}
+
+#[derive(Component)]
+struct HopPlannerText;
+
+fn establish_outpost_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player: Single<(&Grounded, &LandingZone), With<Player>>,
+    mut land_pads: Query<(&mut LandPad, &GlobalTransform, &Children)>,
+    mut labels: Query<&mut Text2d, With<LandPadLabel>>,
+    mut credits: ResMut<Credits>,
+    mut outposts: ResMut<Outposts>,
+    mut explored_terrain: ResMut<ExploredTerrain>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::KeyO) || !player.0.0 || credits.0 < OUTPOST_COST {
+        return;
+    }
+
+    let Some(Ok((mut land_pad, pad_transform, children))) =
+        player.1.0.map(|pad| land_pads.get_mut(pad))
+    else {
+        return;
+    };
+
+    if land_pad.kind != LandPadKind::Standard {
+        return;
+    }
+
+    credits.0 -= OUTPOST_COST;
+
+    land_pad.kind = LandPadKind::Outpost;
+    land_pad.base_multiplier = 1.0;
+    land_pad.score_multiplier = 1.0;
+
+    let pad_center = pad_transform.translation().truncate();
+    let pad_surface = Vec2::new(pad_center.x, pad_center.y - LAND_PAD_SENSOR_HEIGHT / 2.0);
+    outposts.0.insert(land_pad.chunk_origin, pad_surface);
+    explored_terrain
+        .pads
+        .insert(land_pad.chunk_origin, (pad_surface, LandPadKind::Outpost));
+
+    for &child in children {
+        if let Ok(mut text) = labels.get_mut(child) {
+            text.0 = "OUTPOST".to_string();
+        }
+    }
+}
+
+fn hop_fuel_cost(distance: f32) -> u32 {
+    (distance.abs() * HOP_FUEL_PER_UNIT).ceil() as u32
+}
+
+/// Opens hop planning on the map view when the lander is docked at an outpost.
+fn setup_hop_planner(
+    mut commands: Commands,
+    player: Single<(&LandingZone, Has<Servicing>, &Transform), With<Player>>,
+    land_pads: Query<&LandPad>,
+    outposts: Res<Outposts>,
+    font: Res<MainFont>,
+) {
+    let (landing_zone, servicing, transform) = *player;
+
+    let Some(current_outpost) = landing_zone
+        .0
+        .and_then(|pad| land_pads.get(pad).ok())
+        .filter(|land_pad| servicing && land_pad.kind == LandPadKind::Outpost)
+        .map(|land_pad| land_pad.chunk_origin)
+    else {
+        return;
+    };
+
+    let lander_x = transform.translation.x;
+
+    let mut destinations: Vec<Vec2> = outposts
+        .0
+        .iter()
+        .filter(|(chunk_origin, _)| **chunk_origin != current_outpost)
+        .map(|(_, position)| *position)
+        .collect();
+    // Nearest first, so the default pick is the cheapest hop
+    destinations.sort_by(|a, b| (a.x - lander_x).abs().total_cmp(&(b.x - lander_x).abs()));
+
+    commands.insert_resource(HopPlanner {
+        destinations,
+        selected: 0,
+    });
+
+    commands.spawn((
+        DespawnOnExit(GamePhase::Map),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        children![(
+            HopPlannerText,
+            Text::default(),
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            font.text_font(TextStyle::Panel),
+            TextBackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        )],
+    ));
+}
+
+fn cleanup_hop_planner(mut commands: Commands) {
+    commands.remove_resource::<HopPlanner>();
+}
+
+/// Cycles through the established outposts on the map and hops to the picked one.
+fn hop_planner_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut player: Single<
+        (
+            &mut LandingZone,
+            &mut Transform,
+            &mut LinearVelocity,
+            &mut AngularVelocity,
+            &mut Fuel,
+        ),
+        With<Player>,
+    >,
+    mut hop_planner: ResMut<HopPlanner>,
+    mut hop_planner_text: Single<&mut Text, With<HopPlannerText>>,
+    mut gizmos: Gizmos,
+    camera: Single<&Projection, With<Camera>>,
+    mut time_passed: ResMut<TimePassed>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    let lander_x = player.1.translation.x;
+
+    let Some(&destination) = hop_planner.destinations.get(hop_planner.selected) else {
+        hop_planner_text.0 = "HOP: NO OTHER OUTPOSTS ESTABLISHED".to_string();
+        return;
+    };
+
+    let distance = destination.x - lander_x;
+    let fuel_cost = hop_fuel_cost(distance);
+
+    let scale = match *camera {
+        Projection::Orthographic(perspective) => perspective.scale,
+        _ => 1.0,
+    };
+    gizmos.circle_2d(destination, 16.0 * scale, Color::WHITE);
+    gizmos.line_2d(player.1.translation.truncate(), destination, Color::WHITE);
+
+    hop_planner_text.0 = format!(
+        "HOP TO OUTPOST {}/{}: {:.0} m {}, {} fuel{}\nTAB TO PICK ANOTHER, ENTER TO HOP",
+        hop_planner.selected + 1,
+        hop_planner.destinations.len(),
+        distance.abs(),
+        if distance < 0.0 { "WEST" } else { "EAST" },
+        fuel_cost,
+        if fuel_cost > player.4.0 {
+            " (NOT ENOUGH FUEL)"
+        } else {
+            ""
+        }
+    );
+
+    if keyboard_input.just_pressed(KeyCode::Tab) {
+        hop_planner.selected = (hop_planner.selected + 1) % hop_planner.destinations.len();
+        return;
+    }
+
+    if !keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter])
+        || fuel_cost > player.4.0
+    {
+        return;
+    }
+
+    player.4.0 -= fuel_cost;
+    time_passed.0 += Duration::from_secs_f32(distance.abs() / HOP_SPEED);
+
+    // The departure pad may stream out before its sensor reports the exit, and
+    // the arrival pad's sensor picks the lander up on the way down.
+    player.0.0 = None;
+
+    player.1.translation = Vec3::new(
+        destination.x,
+        destination.y + HOP_APPROACH_HEIGHT,
+        player.1.translation.z,
+    );
+    player.1.rotation = Quat::IDENTITY;
+    player.2.0 = Vec2::new(0.0, -HOP_APPROACH_SPEED);
+    player.3.0 = 0.0;
+
+    game_phase.set(GamePhase::Running);
+}
+
+fn setup_outposts(mut commands: Commands) {
+    commands.init_resource::<Outposts>();
+}
+
+fn cleanup_outposts(mut commands: Commands) {
+    commands.remove_resource::<Outposts>();
+}
@@ -0,0 +1,661 @@
+//! The lander itself: pilot input, engine and RCS thrusters, fuel, and ground
+//! contact.
+
+use super::{
+    audio::AudioCue,
+    fleet::FleetLander,
+    outcome::MultiplierVoided,
+    world::{Ground, TerrainQuery, surface_physics},
+    *,
+};
+
+const FUEL_CONSUMPTION_RATE: u32 = 1;
+const GIMBAL_MAX_ANGLE: f32 = 0.1; // radians the thrust vector tilts off the hull axis
+const GIMBAL_FUEL_PENALTY: u32 = 1; // extra fuel per frame while gimballing under thrust
+
+const LEAK_PARTICLE_LIFETIME: f32 = 1.0;
+const LEAK_PARTICLE_SPEED: f32 = 15.0;
+const EXHAUST_GROUND_BOUNCE: f32 = 0.3; // share of the impact speed particles keep off the ground
+
+const ABORT_KEY: KeyCode = KeyCode::KeyB;
+const ABORT_FUEL_COST: u32 = 100; // spent up front to light the recovery burn
+const ABORT_SAFE_CLEARANCE: f32 = 250.0; // height above the terrain the recovery hovers at
+const ABORT_HOLD_GAIN: f32 = 0.5; // desired climb rate per unit below the hover height
+const ABORT_MAX_SINK: f32 = 5.0;
+const ABORT_MAX_TILT: f32 = 0.6;
+
+const TIP_OVER_WINDOW: f32 = 10.0; // seconds a tipped over lander has to get back upright
+const TIP_OVER_RIGHTING_BOOST: f32 = 3.0; // rotation authority multiplier while tipped over
+const TIP_OVER_MAX_RIGHTING_SPEED: f32 = 1.5; // rad/s, faster than this on the ground damages the hull
+
+const RCS_FUEL_RATE: f32 = 4.0; // units per second at full rotation command
+const RCS_PUFF_INTERVAL: f32 = 0.08;
+const RCS_PUFF_LIFETIME: f32 = 0.3;
+const RCS_PUFF_SPEED: f32 = 30.0;
+
+const OPERATIONAL_ZONE_CORRECTION: f32 = 0.02; // downward acceleration per unit above the warning height
+const LOST_CONTACT_DEPTH: f32 = 100.0; // below the terrain surface
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            (control_system, abort_system)
+                .chain()
+                .in_set(FlightSystems::Input),
+            (thruster_system, operational_zone_system)
+                .chain()
+                .in_set(FlightSystems::Thrust),
+            (ground_detection_system, clear_scrub_system, tip_over_system)
+                .chain()
+                .in_set(FlightSystems::Contact),
+            fuel_weight_system,
+            fuel_leak_system,
+            rcs_effects_system,
+            engine_out_system,
+            exhaust_particle_system,
+        )
+            .run_if(in_state(GamePhase::Running)),
+    )
+    .add_systems(Update, animation_system.run_if(in_state(GameState::Game)));
+}
+
+#[derive(Component)]
+pub(super) struct Player;
+
+/// Main engine won't light until the timer runs out.
+#[derive(Component)]
+pub(super) struct EngineOut(pub(super) Timer);
+
+/// What a lander's pilot, human or autopilot, asks of it this frame.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub(super) struct ControlInput {
+    /// Main engine throttle, from 0.0 to 1.0.
+    pub(super) throttle: f32,
+    /// Rotation thruster command, from -1.0 (clockwise) to 1.0 (counter-clockwise).
+    pub(super) rotation: f32,
+    /// Engine gimbal command, from -1.0 (thrust tilted clockwise) to 1.0
+    /// (counter-clockwise).
+    pub(super) gimbal: f32,
+}
+
+#[derive(Component, PartialEq, Eq, Debug)]
+pub(super) enum PlayerState {
+    Idle,
+    Firing,
+    Crashed,
+}
+
+#[derive(Component)]
+pub(super) struct Fuel(pub(super) u32);
+
+/// Punctured fuel tank, losing `rate` units of fuel per second.
+#[derive(Component)]
+pub(super) struct FuelLeak {
+    pub(super) rate: f32,
+    pub(super) lost: f32,
+    pub(super) particle_timer: Timer,
+}
+
+/// The autopilot has taken over to climb away from the ground and hover.
+#[derive(Component)]
+pub(super) struct AbortRecovery;
+
+/// The lander came to rest on its side and has until `window` runs out to get
+/// back upright. The window only runs while on the ground.
+#[derive(Component)]
+pub(super) struct TippedOver {
+    pub(super) window: Timer,
+}
+
+/// Propellant of the rotation thrusters, only tracked in realism mode.
+#[derive(Component)]
+pub(super) struct RcsFuel {
+    pub(super) amount: f32,
+    puff_timer: Timer,
+    firing: bool,
+}
+
+impl RcsFuel {
+    pub(super) fn full() -> Self {
+        RcsFuel {
+            amount: MAX_RCS_FUEL,
+            puff_timer: Timer::from_seconds(RCS_PUFF_INTERVAL, TimerMode::Repeating),
+            firing: false,
+        }
+    }
+}
+
+#[derive(Component)]
+pub(super) struct ExhaustParticle {
+    pub(super) velocity: Vec2,
+    pub(super) lifetime: Timer,
+}
+
+/// Velocity change produced by the main engine so far, in m/s.
+#[derive(Component)]
+pub(super) struct DeltaVSpent(pub(super) f32);
+
+#[derive(Component)]
+pub(super) struct Grounded(pub(super) bool);
+
+/// The lander touched a pad obstruction, so it can't land until it lifts off
+/// again.
+#[derive(Component)]
+pub(super) struct Scrubbed;
+
+#[derive(Resource)]
+pub(super) struct LanderSprite {
+    pub(super) texture: Handle<Image>,
+    pub(super) layout: Handle<TextureAtlasLayout>,
+}
+
+/// Physics components shared by every player controlled lander and the rival.
+pub(super) fn lander_body(leg_material: SurfaceMaterial) -> impl Bundle {
+    (
+        RigidBody::Dynamic,
+        CollisionEventsEnabled,
+        CollisionLayers::new(GameLayer::Lander, [GameLayer::Terrain]),
+        Collider::rectangle(LANDER_SIZE.x as f32, LANDER_SIZE.y as f32),
+        Mass(DRY_LANDER_MASS + (MAX_FUEL as f32 * FUEL_MASS_FACTOR)),
+        surface_physics(leg_material),
+    )
+}
+
+fn engine_out_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut landers: Query<(Entity, &mut EngineOut)>,
+) {
+    for (entity, mut engine_out) in &mut landers {
+        engine_out.0.tick(time.delta());
+        if engine_out.0.is_finished() {
+            commands.entity(entity).remove::<EngineOut>();
+        }
+    }
+}
+
+fn control_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut player: Single<(&mut ControlInput, &Fuel), With<Player>>,
+    lifetime_stats: Res<LifetimeStats>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    let mut control = ControlInput::default();
+
+    let mut steering = 0.0;
+    if keyboard_input.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        steering += 1.0;
+    }
+    if keyboard_input.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        steering -= 1.0;
+    }
+
+    // Holding shift steers the engine instead of the hull, once unlocked
+    if lifetime_stats.gimbal_unlocked()
+        && keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    {
+        control.gimbal = steering;
+    } else {
+        control.rotation = steering;
+    }
+
+    if player.1.0 > 0 && keyboard_input.pressed(KeyCode::Space) {
+        control.throttle = 1.0;
+    }
+
+    *player.0 = control;
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        game_phase.set(GamePhase::QuitConfirm);
+    }
+}
+
+/// Toggles the abort recovery of the player's lander and, while it is active,
+/// overrides its controls with a full-thrust climb to a safe height followed by
+/// a hover. Fleet landers keep recovering after the focus moves on.
+fn abort_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut landers: Query<
+        (
+            Entity,
+            &mut ControlInput,
+            &mut Fuel,
+            &Transform,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Mass,
+            Has<AbortRecovery>,
+            Has<Player>,
+        ),
+        Or<(With<Player>, With<AbortRecovery>)>,
+    >,
+    terrain: TerrainQuery,
+) {
+    let toggled = keyboard_input.just_pressed(ABORT_KEY);
+
+    for (
+        entity,
+        mut control,
+        mut fuel,
+        transform,
+        linear_velocity,
+        angular_velocity,
+        mass,
+        mut aborting,
+        player,
+    ) in &mut landers
+    {
+        if player && toggled {
+            if aborting {
+                commands.entity(entity).remove::<AbortRecovery>();
+                continue;
+            }
+
+            commands
+                .entity(entity)
+                .insert((AbortRecovery, MultiplierVoided));
+            fuel.0 = fuel.0.saturating_sub(ABORT_FUEL_COST);
+            aborting = true;
+        }
+
+        if !aborting {
+            continue;
+        }
+
+        let ground_height = terrain.height_at(transform.translation.x);
+
+        let state = autopilot::LanderState {
+            position: transform.translation.truncate(),
+            velocity: linear_velocity.0,
+            angle: transform.rotation.to_euler(EulerRot::XYZ).2,
+            angular_velocity: angular_velocity.0,
+            max_acceleration: THRUST / mass.0,
+            gravity: -GRAVITY.y,
+            ground_height,
+        };
+
+        // Far below the hover height this asks for more than the engine has,
+        // so the climb runs at full thrust
+        let hover_height = ground_height + ABORT_SAFE_CLEARANCE;
+        let desired_velocity = Vec2::new(
+            0.0,
+            ((hover_height - state.position.y) * ABORT_HOLD_GAIN).max(-ABORT_MAX_SINK),
+        );
+        let desired_acceleration =
+            desired_velocity - state.velocity + Vec2::new(0.0, state.gravity);
+
+        *control = autopilot::hold_acceleration(&state, desired_acceleration, ABORT_MAX_TILT);
+    }
+}
+
+fn thruster_system(
+    time: Res<Time>,
+    mut audio_cues: MessageWriter<AudioCue>,
+    mut landers: Query<(
+        Entity,
+        &Transform,
+        Forces,
+        &ControlInput,
+        &mut PlayerState,
+        &mut Fuel,
+        &Mass,
+        &mut DeltaVSpent,
+        Option<&mut RcsFuel>,
+        Has<TippedOver>,
+        Has<EngineOut>,
+    )>,
+) {
+    for (
+        entity,
+        transform,
+        mut forces,
+        control,
+        mut state,
+        mut fuel,
+        mass,
+        mut delta_v_spent,
+        rcs_fuel,
+        tipped_over,
+        engine_out,
+    ) in &mut landers
+    {
+        if *state == PlayerState::Crashed {
+            continue;
+        }
+
+        let rotation = if tipped_over {
+            control.rotation * TIP_OVER_RIGHTING_BOOST
+        } else {
+            control.rotation
+        };
+
+        let rcs_available = match rcs_fuel {
+            Some(mut rcs_fuel) if rotation != 0.0 && rcs_fuel.amount > 0.0 => {
+                rcs_fuel.amount =
+                    (rcs_fuel.amount - rotation.abs() * RCS_FUEL_RATE * time.delta_secs()).max(0.0);
+                true
+            }
+            Some(_) => false,
+            None => true,
+        };
+
+        if rotation != 0.0 && rcs_available {
+            forces.apply_angular_acceleration(rotation * ROTATION_THRUST);
+        }
+
+        if control.throttle > 0.0 && fuel.0 > 0 && !engine_out {
+            // The gimbal tilts the thrust through the center of mass, so it pushes
+            // sideways without turning the hull
+            let gimbal = Quat::from_rotation_z(control.gimbal * GIMBAL_MAX_ANGLE);
+            let force_vector =
+                (transform.rotation * gimbal * Vec3::Y * THRUST * control.throttle).truncate();
+
+            forces.apply_force(force_vector);
+
+            let consumption = if control.gimbal != 0.0 {
+                FUEL_CONSUMPTION_RATE + GIMBAL_FUEL_PENALTY
+            } else {
+                FUEL_CONSUMPTION_RATE
+            };
+            fuel.0 = fuel.0.saturating_sub(consumption);
+            delta_v_spent.0 += THRUST * control.throttle / mass.0 * time.delta_secs();
+
+            if *state != PlayerState::Firing {
+                *state = PlayerState::Firing;
+                audio_cues.write(AudioCue::ThrustStarted(entity));
+            }
+        } else if *state == PlayerState::Firing {
+            *state = PlayerState::Idle;
+            audio_cues.write(AudioCue::ThrustStopped(entity));
+        }
+    }
+}
+
+fn operational_zone_system(
+    mut commands: Commands,
+    mut player: Single<(&Transform, Forces), With<Player>>,
+    terrain: TerrainQuery,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+    mut audio_cues: MessageWriter<AudioCue>,
+    mut warned: Local<bool>,
+) {
+    let position = player.0.translation;
+
+    let warning = position.y > OPERATIONAL_ZONE_WARNING_HEIGHT;
+    if warning && !*warned {
+        audio_cues.write(AudioCue::Warning);
+    }
+    *warned = warning;
+
+    if position.y > OPERATIONAL_ZONE_ABORT_HEIGHT {
+        commands.insert_resource(LoseReason::LeftOperationalZone);
+        game_phase.set(GamePhase::Lose);
+        return;
+    }
+
+    if terrain.ground_distance(position.truncate()) < -LOST_CONTACT_DEPTH {
+        commands.insert_resource(LoseReason::LostContact);
+        game_phase.set(GamePhase::Lose);
+        return;
+    }
+
+    if position.y > OPERATIONAL_ZONE_WARNING_HEIGHT {
+        let excess = position.y - OPERATIONAL_ZONE_WARNING_HEIGHT;
+        player
+            .1
+            .apply_linear_acceleration(Vec2::new(0.0, -excess * OPERATIONAL_ZONE_CORRECTION));
+    }
+}
+
+fn animation_system(mut landers: Query<(&PlayerState, &mut Sprite), Changed<PlayerState>>) {
+    for (state, mut sprite) in &mut landers {
+        sprite.texture_atlas.as_mut().unwrap().index = match state {
+            PlayerState::Idle => 0,
+            PlayerState::Firing => 1,
+            PlayerState::Crashed => 2,
+        };
+    }
+}
+
+/// Exhaust puffs of the rotation thrusters in realism mode.
+fn rcs_effects_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut landers: Query<(Entity, &ControlInput, &Transform, &mut RcsFuel)>,
+    mut audio_cues: MessageWriter<AudioCue>,
+) {
+    for (entity, control, transform, mut rcs_fuel) in &mut landers {
+        let firing = control.rotation != 0.0 && rcs_fuel.amount > 0.0;
+
+        if firing != rcs_fuel.firing {
+            rcs_fuel.firing = firing;
+            audio_cues.write(if firing {
+                AudioCue::RcsStarted(entity)
+            } else {
+                AudioCue::RcsStopped(entity)
+            });
+        }
+
+        if !firing {
+            continue;
+        }
+
+        rcs_fuel.puff_timer.tick(time.delta());
+
+        // Counter-clockwise rotation fires the nozzle on the right of the nose
+        let side = control.rotation.signum();
+        let nozzle = Vec3::new(
+            side * LANDER_SIZE.x as f32 / 2.0,
+            LANDER_SIZE.y as f32 / 4.0,
+            0.1,
+        );
+        let direction = (transform.rotation * Vec3::new(side, 0.0, 0.0)).truncate();
+
+        for _ in 0..rcs_fuel.puff_timer.times_finished_this_tick() {
+            commands.spawn((
+                DespawnOnExit(GameState::Game),
+                ExhaustParticle {
+                    velocity: direction * RCS_PUFF_SPEED,
+                    lifetime: Timer::from_seconds(RCS_PUFF_LIFETIME, TimerMode::Once),
+                },
+                Sprite::from_color(Color::WHITE, Vec2::splat(1.5)),
+                Transform::from_translation(transform.translation + transform.rotation * nozzle),
+            ));
+        }
+    }
+}
+
+fn fuel_leak_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut leaking: Query<(&mut FuelLeak, &mut Fuel, &Transform)>,
+) {
+    let mut rng = rand::rng();
+
+    for (mut leak, mut fuel, transform) in &mut leaking {
+        leak.lost += leak.rate * time.delta_secs();
+        let whole_units = leak.lost.floor();
+        leak.lost -= whole_units;
+        if whole_units > 0.0 {
+            fuel.0 = fuel.0.saturating_sub(whole_units as u32);
+        }
+
+        leak.particle_timer.tick(time.delta());
+        if fuel.0 == 0 {
+            continue;
+        }
+
+        for _ in 0..leak.particle_timer.times_finished_this_tick() {
+            let origin = transform.translation
+                + transform.rotation * Vec3::new(0.0, -(LANDER_SIZE.y as f32) / 4.0, 0.1);
+            let direction = (transform.rotation * Vec3::NEG_X).truncate();
+
+            commands.spawn((
+                DespawnOnExit(GameState::Game),
+                ExhaustParticle {
+                    velocity: (direction + Vec2::new(0.0, rng.random_range(-0.3..0.3)))
+                        * LEAK_PARTICLE_SPEED,
+                    lifetime: Timer::from_seconds(LEAK_PARTICLE_LIFETIME, TimerMode::Once),
+                },
+                Sprite::from_color(Color::WHITE, Vec2::splat(2.0)),
+                Transform::from_translation(origin),
+            ));
+        }
+    }
+}
+
+fn exhaust_particle_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: TerrainQuery,
+    mut particles: Query<(Entity, &mut ExhaustParticle, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in &mut particles {
+        particle.lifetime.tick(time.delta());
+        if particle.lifetime.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        particle.velocity += GRAVITY * time.delta_secs();
+        transform.translation += (particle.velocity * time.delta_secs()).extend(0.0);
+
+        // Exhaust hitting the ground kicks up along it instead of sinking in
+        let position = transform.translation.truncate();
+        if terrain.ground_distance(position) < 0.0 {
+            let normal = Vec2::new(-terrain.slope_at(position.x), 1.0).normalize();
+            let into_ground = particle.velocity.dot(normal);
+            if into_ground < 0.0 {
+                particle.velocity -= (1.0 + EXHAUST_GROUND_BOUNCE) * into_ground * normal;
+            }
+            transform.translation.y = terrain.height_at(position.x);
+        }
+        sprite.color = Color::WHITE.with_alpha(particle.lifetime.fraction_remaining());
+    }
+}
+
+/// Lifting off clears a scrubbed landing, for another try.
+fn clear_scrub_system(mut commands: Commands, landers: Query<(Entity, &Grounded), With<Scrubbed>>) {
+    for (entity, grounded) in &landers {
+        if !grounded.0 {
+            commands.entity(entity).remove::<Scrubbed>();
+        }
+    }
+}
+
+/// Gives a lander resting past the safe tilt a window to rotate back upright,
+/// with extra rotation authority, instead of leaving it stuck. Righting it too
+/// fast scrapes the hull open, and running out of time ends the run, or just
+/// that lander's flight in a fleet run. Fleet landers keep their window while
+/// the focus is elsewhere.
+fn tip_over_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut landers: Query<
+        (
+            Entity,
+            &Transform,
+            &Grounded,
+            &AngularVelocity,
+            &mut PlayerState,
+            Option<&mut TippedOver>,
+            Has<FuelLeak>,
+        ),
+        Or<(With<Player>, With<FleetLander>)>,
+    >,
+    rules: Res<GameRules>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    for (entity, transform, grounded, angular_velocity, mut state, tipped_over, leaking) in
+        &mut landers
+    {
+        if *state == PlayerState::Crashed {
+            continue;
+        }
+
+        let tilt = transform.rotation.to_euler(EulerRot::XYZ).2.abs();
+        let tipped = tilt >= rules.win_tolerances.max_tilt;
+        let grounded = grounded.0;
+
+        let Some(mut tipped_over) = tipped_over else {
+            if tipped && grounded {
+                commands.entity(entity).insert(TippedOver {
+                    window: Timer::from_seconds(TIP_OVER_WINDOW, TimerMode::Once),
+                });
+            }
+            continue;
+        };
+
+        // Only getting back upright ends the window. Bouncing clear just pauses it,
+        // so hopping about on the side can't restart the countdown
+        if !tipped {
+            commands.entity(entity).remove::<TippedOver>();
+            continue;
+        }
+
+        if !grounded {
+            continue;
+        }
+
+        if angular_velocity.0.abs() > TIP_OVER_MAX_RIGHTING_SPEED && !leaking {
+            commands.entity(entity).insert(FuelLeak {
+                rate: MIN_FUEL_LEAK_RATE,
+                lost: 0.0,
+                particle_timer: Timer::from_seconds(LEAK_PARTICLE_INTERVAL, TimerMode::Repeating),
+            });
+        }
+
+        tipped_over.window.tick(time.delta());
+        if !tipped_over.window.is_finished() {
+            continue;
+        }
+
+        if rules.fleet {
+            // The rest of the fleet keeps flying, fleet_focus_system ends the run
+            *state = PlayerState::Crashed;
+            commands
+                .entity(entity)
+                .insert((ControlInput::default(), LockedAxes::ALL_LOCKED));
+        } else {
+            commands.insert_resource(LoseReason::TippedOver);
+            game_phase.set(GamePhase::Lose);
+        }
+    }
+}
+
+/// Grounded while any ground collider touches the lander. The terrain, the pads
+/// and their obstructions are separate colliders, so sliding from one onto
+/// another ends one contact while the lander still rests on the other.
+fn ground_detection_system(
+    mut landers: Query<(Entity, &mut Grounded)>,
+    ground_query: Query<(), With<Ground>>,
+    collisions: Collisions,
+) {
+    for (entity, mut grounded) in &mut landers {
+        let touching_ground = collisions
+            .collisions_with(entity)
+            .filter(|contact_pair| contact_pair.is_touching())
+            .any(|contact_pair| {
+                let other = if contact_pair.collider1 == entity {
+                    contact_pair.collider2
+                } else {
+                    contact_pair.collider1
+                };
+                ground_query.contains(other)
+            });
+
+        if grounded.0 != touching_ground {
+            grounded.0 = touching_ground;
+        }
+    }
+}
+
+fn fuel_weight_system(mut landers: Query<(&mut Mass, &Fuel), Changed<Fuel>>) {
+    for (mut mass, fuel) in &mut landers {
+        let empty_mass = DRY_LANDER_MASS;
+        let fuel_mass = fuel.0 as f32 * FUEL_MASS_FACTOR;
+        mass.0 = empty_mass + fuel_mass;
+    }
+}
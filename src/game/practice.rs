@@ -0,0 +1,65 @@
+//! Practice runs, started above a chosen pad of the seed and restarted on the
+//! spot.
+
+use super::{world::standard_pads, *};
+
+const PRACTICE_SPAWN_HEIGHT: f32 = 150.0; // above the pad surface
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PracticePad>().add_systems(
+        Update,
+        practice_input_system.run_if(
+            in_state(GameState::Game)
+                .and(practice_enabled)
+                .and(not(in_state(GamePhase::Map)))
+                .and(not(in_state(GamePhase::QuitConfirm))),
+        ),
+    );
+}
+
+/// Which pad of the seed practice runs start above, counting from the start.
+#[derive(Resource, Default)]
+pub(super) struct PracticePad(pub(super) usize);
+
+/// World position practice runs start at, above the `practice_pad`-th standard
+/// pad right of the start, if there is one within reach.
+pub(super) fn practice_spawn(
+    terrain_generator: &TerrainGenerator,
+    practice_pad: &PracticePad,
+) -> Option<Vec2> {
+    standard_pads(terrain_generator)
+        .nth(practice_pad.0)
+        .map(|(pad_surface, _)| pad_surface + Vec2::new(0.0, PRACTICE_SPAWN_HEIGHT))
+}
+
+/// Restarts practice runs on the spot, R on the same pad, [ and ] on the
+/// previous or next one, wrapping around past either end.
+fn practice_input_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    terrain_seed: Res<TerrainSeed>,
+    terrain: Res<Terrain>,
+    mut practice_pad: ResMut<PracticePad>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let previous = keyboard_input.just_pressed(KeyCode::BracketLeft);
+    let next = keyboard_input.just_pressed(KeyCode::BracketRight);
+
+    if previous || next {
+        let pad_count = standard_pads(&terrain.0).count().max(1);
+        practice_pad.0 = if previous {
+            (practice_pad.0 + pad_count - 1) % pad_count
+        } else {
+            (practice_pad.0 + 1) % pad_count
+        };
+    } else if !keyboard_input.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+
+    commands.insert_resource(RetrySeed(terrain_seed.0));
+    game_state.set(GameState::Menu);
+}
+
+fn practice_enabled(rules: Res<GameRules>) -> bool {
+    rules.practice
+}
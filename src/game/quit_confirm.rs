@@ -0,0 +1,83 @@
+//! The dialog pausing the run to ask whether to quit it.
+
+use super::{outcome::format_time, *};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        quit_confirm_input_system.run_if(in_state(GamePhase::QuitConfirm)),
+    )
+    .add_systems(OnEnter(GamePhase::QuitConfirm), setup_quit_confirm)
+    .add_systems(OnExit(GamePhase::QuitConfirm), cleanup_quit_confirm);
+}
+
+/// Pauses the run behind a dialog summarizing it so far.
+fn setup_quit_confirm(
+    mut commands: Commands,
+    player: Single<&Fuel, With<Player>>,
+    time_passed: Res<TimePassed>,
+    flight_recorder: Res<FlightRecorder>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    font: Res<MainFont>,
+) {
+    virtual_time.pause();
+
+    commands.spawn((
+        DespawnOnExit(GamePhase::QuitConfirm),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        children![(
+            Text::new(format!(
+                "Quit this run?\nTime: {}  Distance: {:.0} m  Fuel used: {}\nPress ENTER to quit to the menu.\nPress M to open the map.\nPress ESC to keep flying.",
+                format_time(time_passed.0.as_secs_f32()),
+                flight_recorder.distance,
+                flight_recorder.fuel_used(player.0)
+            )),
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            font.text_font(TextStyle::Dialog),
+            TextBackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        )],
+    ));
+}
+
+fn cleanup_quit_confirm(mut virtual_time: ResMut<Time<Virtual>>) {
+    virtual_time.unpause();
+}
+
+/// Quitting still counts the run, so it shows up in the lifetime statistics.
+fn quit_confirm_input_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    player: Single<&Transform, With<Player>>,
+    terrain_seed: Res<TerrainSeed>,
+    time_passed: Res<TimePassed>,
+    rules: Res<GameRules>,
+    flight_recorder: Res<FlightRecorder>,
+    mut runs_finished: MessageWriter<RunFinished>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut game_phase: ResMut<NextState<GamePhase>>,
+) {
+    if keyboard_input.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]) {
+        runs_finished.write(RunFinished {
+            outcome: RunOutcome::Quit,
+            duration: time_passed.0,
+            distance: flight_recorder.distance,
+            score: None,
+            seed: terrain_seed.0,
+            position: player.translation.truncate(),
+            practice: rules.practice,
+        });
+        game_state.set(GameState::Menu);
+    } else if keyboard_input.just_pressed(KeyCode::KeyM) {
+        game_phase.set(GamePhase::Map);
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        game_phase.set(GamePhase::Running);
+    }
+}
@@ -0,0 +1,110 @@
+//! Recording the run: flight recorder samples, their export, the run log and
+//! the context attached to crash reports.
+
+use super::*;
+
+pub(super) const TELEMETRY_SAMPLE_INTERVAL: f32 = 0.1;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), setup_recording)
+        .add_systems(
+            Update,
+            (
+                flight_recorder_system.run_if(in_state(GamePhase::Running)),
+                telemetry_export_system,
+                phase_log_system,
+                crash_context_system,
+            )
+                .run_if(in_state(GameState::Game)),
+        )
+        .add_systems(OnExit(GameState::Game), cleanup_recording);
+}
+
+fn setup_recording(mut commands: Commands) {
+    commands.insert_resource(FlightRecorder::new(TELEMETRY_SAMPLE_INTERVAL));
+}
+
+fn cleanup_recording(mut commands: Commands) {
+    commands.remove_resource::<FlightRecorder>();
+
+    crash_report::set_run_summary(None);
+}
+
+fn flight_recorder_system(
+    time: Res<Time>,
+    time_passed: Res<TimePassed>,
+    player: Single<(&Transform, &LinearVelocity, &Fuel, &ControlInput), With<Player>>,
+    mut flight_recorder: ResMut<FlightRecorder>,
+) {
+    flight_recorder.timer.tick(time.delta());
+    if !flight_recorder.timer.just_finished() && !flight_recorder.samples.is_empty() {
+        return;
+    }
+
+    flight_recorder.record(TelemetrySample {
+        time: time_passed.0.as_secs_f32(),
+        position: player.0.translation.truncate(),
+        velocity: player.1.0,
+        angle: player.0.rotation.to_euler(EulerRot::XYZ).2,
+        fuel: player.2.0,
+        throttle: player.3.throttle,
+    });
+}
+
+fn telemetry_export_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    flight_recorder: Res<FlightRecorder>,
+    terrain_seed: Res<TerrainSeed>,
+    lifetime_stats: Res<LifetimeStats>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F6) {
+        return;
+    }
+
+    match telemetry::export(&flight_recorder, terrain_seed.0, &lifetime_stats) {
+        Ok(dir) => info!("Exported telemetry to {}", dir.display()),
+        Err(error) => warn!("Could not export telemetry: {}", error),
+    }
+}
+
+fn phase_log_system(
+    mut transitions: MessageReader<StateTransitionEvent<GamePhase>>,
+    mut run_events: MessageWriter<RunEvent>,
+) {
+    for transition in transitions.read() {
+        let Some(phase) = &transition.entered else {
+            continue;
+        };
+
+        run_events.write(RunEvent::PhaseChanged {
+            phase: match phase {
+                GamePhase::Running => "running",
+                GamePhase::Map => "map",
+                GamePhase::QuitConfirm => "quit_confirm",
+                GamePhase::Win => "win",
+                GamePhase::Lose => "lose",
+            },
+        });
+    }
+}
+
+/// Keeps the run summary of crash reports up to date.
+fn crash_context_system(
+    player: Single<(&Transform, &LinearVelocity, &Fuel, &PlayerState), With<Player>>,
+    phase: Res<State<GamePhase>>,
+    seed: Res<TerrainSeed>,
+    time_passed: Res<TimePassed>,
+) {
+    crash_report::set_run_summary(Some(format!(
+        "seed {}, phase {:?}, time {:.1}s, position ({:.1}, {:.1}), velocity ({:.1}, {:.1}), fuel {}, state {:?}",
+        seed.0,
+        phase.get(),
+        time_passed.0.as_secs_f32(),
+        player.0.translation.x,
+        player.0.translation.y,
+        player.1.x,
+        player.1.y,
+        player.2.0,
+        player.3
+    )));
+}
@@ -0,0 +1,261 @@
+//! Replays of the last run from the flight recorder, watched from the end
+//! screens with a ghost lander and a timeline.
+
+use super::{camera::framed_center, outcome::EndScreen, recording::TELEMETRY_SAMPLE_INTERVAL, *};
+
+const REPLAY_KEY: KeyCode = KeyCode::KeyV;
+const REPLAY_SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+const REPLAY_SEEK_STEP: f32 = 1.0; // seconds skipped by the arrow keys
+const REPLAY_FINAL_SECONDS: f32 = 2.0; // End jumps to this long before the end of the run
+const REPLAY_TIMELINE_WIDTH: usize = 40; // characters in the timeline bar
+const REPLAY_GHOST_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.7);
+const REPLAY_HELP: &str =
+    "P: PAUSE  , .: STEP  - =: SPEED  ARROWS: SEEK  HOME: START  END: LAST SECONDS  V: EXIT";
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            replay_toggle_system,
+            replay_system.run_if(resource_exists::<Replay>),
+        )
+            .chain()
+            .run_if(in_state(GamePhase::Win).or(in_state(GamePhase::Lose))),
+    )
+    .add_systems(OnExit(GameState::Game), cleanup_replay);
+}
+
+/// Playback of the flight recorder samples, watched from the end screens.
+#[derive(Resource)]
+struct Replay {
+    /// Run time being shown, in the samples' clock.
+    time: f32,
+    speed: f32,
+    paused: bool,
+}
+
+#[derive(Component)]
+struct ReplayGhost;
+
+#[derive(Component)]
+struct ReplayTimeline;
+
+/// Starts or leaves the replay, swapping the end screen for a ghost lander
+/// flying the recorded samples and a timeline.
+fn replay_toggle_system(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    replay: Option<Res<Replay>>,
+    flight_recorder: Res<FlightRecorder>,
+    lander_sprite: Res<LanderSprite>,
+    font: Res<MainFont>,
+    replay_entities: Query<Entity, Or<(With<ReplayGhost>, With<ReplayTimeline>)>>,
+    mut end_screens: Query<&mut Visibility, With<EndScreen>>,
+) {
+    if !keyboard_input.just_pressed(REPLAY_KEY) {
+        return;
+    }
+
+    let Some(first_sample) = flight_recorder.samples.first() else {
+        return;
+    };
+
+    let replaying = replay.is_none();
+    for mut visibility in &mut end_screens {
+        *visibility = if replaying {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+
+    if !replaying {
+        commands.remove_resource::<Replay>();
+        for entity in &replay_entities {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    commands.insert_resource(Replay {
+        time: first_sample.time,
+        speed: 1.0,
+        paused: false,
+    });
+
+    let mut sprite = Sprite::from_atlas_image(
+        lander_sprite.texture.clone(),
+        TextureAtlas {
+            layout: lander_sprite.layout.clone(),
+            index: 0,
+        },
+    );
+    sprite.color = REPLAY_GHOST_COLOR;
+
+    commands.spawn((
+        DespawnOnExit(GameState::Game),
+        ReplayGhost,
+        sprite,
+        Transform::from_translation(first_sample.position.extend(1.0)),
+    ));
+
+    commands.spawn((
+        DespawnOnExit(GameState::Game),
+        ReplayTimeline,
+        Text::new(""),
+        TextColor(Color::WHITE),
+        font.text_font(TextStyle::HudValue),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: px(16),
+            left: px(16),
+            ..default()
+        },
+    ));
+}
+
+/// Lander position and angle at `time`, re-simulated from the last recorded
+/// sample before it with the acceleration it had until the next one.
+fn replay_state_at(samples: &[TelemetrySample], time: f32) -> (Vec2, Vec2, f32, f32) {
+    let index = samples
+        .partition_point(|sample| sample.time <= time)
+        .saturating_sub(1);
+    let keyframe = &samples[index];
+
+    let Some(next) = samples.get(index + 1) else {
+        return (
+            keyframe.position,
+            keyframe.velocity,
+            keyframe.angle,
+            keyframe.throttle,
+        );
+    };
+
+    let interval = next.time - keyframe.time;
+    let elapsed = (time - keyframe.time).clamp(0.0, interval);
+    let acceleration = (next.velocity - keyframe.velocity) / interval;
+
+    let position =
+        keyframe.position + keyframe.velocity * elapsed + acceleration * elapsed * elapsed / 2.0;
+    let velocity = keyframe.velocity + acceleration * elapsed;
+    // Shortest way round, the recorded angles wrap at half a turn
+    let turn = (next.angle - keyframe.angle + PI).rem_euclid(2.0 * PI) - PI;
+    let angle = keyframe.angle + turn * elapsed / interval;
+
+    (position, velocity, angle, keyframe.throttle)
+}
+
+/// Plays the replay: P pauses, comma and period step one sample, minus and equal
+/// change the speed, the arrows seek, Home and End jump to the start and to the
+/// last seconds of the run.
+fn replay_system(
+    time: Res<Time<Real>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut replay: ResMut<Replay>,
+    flight_recorder: Res<FlightRecorder>,
+    mut ghost: Single<(&mut Transform, &mut Sprite), With<ReplayGhost>>,
+    mut camera: Single<(&mut Transform, &Projection), (With<Camera>, Without<ReplayGhost>)>,
+    mut background: Single<
+        &mut Transform,
+        (With<BackgroundSky>, Without<Camera>, Without<ReplayGhost>),
+    >,
+    mut timeline: Single<&mut Text, With<ReplayTimeline>>,
+    rules: Res<GameRules>,
+) {
+    let samples = &flight_recorder.samples;
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return;
+    };
+    let (start, end) = (first.time, last.time);
+
+    let speed_index = REPLAY_SPEEDS
+        .iter()
+        .position(|&speed| speed == replay.speed)
+        .unwrap_or(2);
+
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        replay.paused = !replay.paused;
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        replay.speed = REPLAY_SPEEDS[speed_index.saturating_sub(1)];
+    }
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        replay.speed = REPLAY_SPEEDS[(speed_index + 1).min(REPLAY_SPEEDS.len() - 1)];
+    }
+    if keyboard_input.just_pressed(KeyCode::Comma) {
+        replay.paused = true;
+        replay.time -= TELEMETRY_SAMPLE_INTERVAL;
+    }
+    if keyboard_input.just_pressed(KeyCode::Period) {
+        replay.paused = true;
+        replay.time += TELEMETRY_SAMPLE_INTERVAL;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        replay.time -= REPLAY_SEEK_STEP;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        replay.time += REPLAY_SEEK_STEP;
+    }
+    if keyboard_input.just_pressed(KeyCode::Home) {
+        replay.time = start;
+    }
+    if keyboard_input.just_pressed(KeyCode::End) {
+        replay.time = end - REPLAY_FINAL_SECONDS;
+    }
+
+    if !replay.paused {
+        replay.time += time.delta_secs() * replay.speed;
+    }
+    replay.time = replay.time.clamp(start, end);
+
+    let (position, velocity, angle, throttle) = replay_state_at(samples, replay.time);
+
+    ghost.0.translation = position.extend(ghost.0.translation.z);
+    ghost.0.rotation = Quat::from_rotation_z(angle);
+    if let Some(atlas) = ghost.1.texture_atlas.as_mut() {
+        atlas.index = if throttle > 0.0 { 1 } else { 0 };
+    }
+
+    // Framed like the live camera, so the ghost stays in view on the way down
+    if let Projection::Orthographic(perspective) = camera.1 {
+        let center = framed_center(
+            camera.0.translation.truncate(),
+            position,
+            velocity,
+            rules.camera_framing,
+            perspective.area.size(),
+        );
+        camera.0.translation.x = center.x;
+        camera.0.translation.y = center.y;
+        background.translation.x = center.x;
+        background.translation.y = center.y;
+    }
+
+    let progress = if end > start {
+        (replay.time - start) / (end - start)
+    } else {
+        1.0
+    };
+    let head = ((progress * REPLAY_TIMELINE_WIDTH as f32) as usize).min(REPLAY_TIMELINE_WIDTH - 1);
+    let bar: String = (0..REPLAY_TIMELINE_WIDTH)
+        .map(|i| match i.cmp(&head) {
+            Ordering::Less => '=',
+            Ordering::Equal => '|',
+            Ordering::Greater => '-',
+        })
+        .collect();
+
+    timeline.0 = format!(
+        "REPLAY {:.2} / {:.2} s  x{}{}\n[{}]\n{}",
+        replay.time - start,
+        end - start,
+        replay.speed,
+        if replay.paused { "  PAUSED" } else { "" },
+        bar,
+        REPLAY_HELP
+    );
+}
+
+fn cleanup_replay(mut commands: Commands) {
+    commands.remove_resource::<Replay>();
+}
@@ -0,0 +1,275 @@
+//! The rival lander racing the player to the pad, flown by the autopilot.
+
+use super::{
+    audio::AudioCue,
+    outcome::stability_issue,
+    world::{Ground, LandPad, TerrainQuery},
+    *,
+};
+
+const RIVAL_SPAWN_OFFSET: f32 = -40.0;
+const RIVAL_SKILL: f32 = 0.8;
+const RIVAL_COLOR: Color = Color::srgb(0.6, 0.8, 1.0);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            (autopilot_target_system, autopilot_system)
+                .chain()
+                .in_set(FlightSystems::Assist),
+            rival_landing_system.after(FlightSystems::Landing),
+        )
+            .run_if(in_state(GamePhase::Running).and(resource_exists::<RivalStatus>)),
+    )
+    .add_systems(OnExit(GameState::Game), cleanup_rival);
+}
+
+/// Flies the lander towards a landing pad.
+#[derive(Component)]
+struct Autopilot {
+    target: Option<Entity>,
+    /// 1.0 flies the controller output as is, lower values add steering noise.
+    skill: f32,
+}
+
+#[derive(Component)]
+pub(super) struct Rival;
+
+#[derive(Component)]
+struct RivalSettleTimer(Timer);
+
+#[derive(Resource, PartialEq)]
+pub(super) enum RivalStatus {
+    Flying,
+    Landed(Duration),
+    Crashed,
+}
+
+pub(super) fn spawn_rival(
+    commands: &mut Commands,
+    lander_sprite: &LanderSprite,
+    leg_material: SurfaceMaterial,
+    rules: &GameRules,
+) {
+    let mut sprite = Sprite::from_atlas_image(
+        lander_sprite.texture.clone(),
+        TextureAtlas {
+            layout: lander_sprite.layout.clone(),
+            index: 0,
+        },
+    );
+    sprite.color = RIVAL_COLOR;
+
+    commands
+        .spawn((
+            DespawnOnExit(GameState::Game),
+            Rival,
+            Autopilot {
+                target: None,
+                skill: RIVAL_SKILL,
+            },
+            RivalSettleTimer(Timer::from_seconds(
+                rules.win_tolerances.settle_time,
+                TimerMode::Once,
+            )),
+            Grounded(false),
+            LandingZone(None),
+            DeltaVSpent(0.0),
+            ControlInput::default(),
+            lander_body(leg_material),
+            sprite,
+            PlayerState::Idle,
+            Fuel(MAX_FUEL),
+            Transform {
+                rotation: Quat::from_rotation_z(PI / 2.0),
+                translation: Vec3::new(RIVAL_SPAWN_OFFSET, SPAWN_HEIGHT, 0.0),
+                ..Default::default()
+            },
+            LinearVelocity(Vec2::new(INITIAL_HORIZONTAL_SPEED, 0.0)),
+        ))
+        .observe(rival_crash_observer);
+
+    commands.insert_resource(RivalStatus::Flying);
+}
+
+/// Races the autopilot for the pad the player is going for: the one they are
+/// over, or else the pad nearest to them.
+fn autopilot_target_system(
+    mut autopilots: Query<(&mut Autopilot, &Grounded)>,
+    player: Single<(&Transform, &LandingZone), (With<Player>, Without<Autopilot>)>,
+    land_pads: Query<(Entity, &GlobalTransform), With<LandPad>>,
+) {
+    let (player_transform, landing_zone) = *player;
+
+    let player_pad = landing_zone
+        .0
+        .filter(|pad| land_pads.contains(*pad))
+        .or_else(|| {
+            let player_x = player_transform.translation.x;
+            land_pads
+                .iter()
+                .min_by(|(_, a), (_, b)| {
+                    (a.translation().x - player_x)
+                        .abs()
+                        .total_cmp(&(b.translation().x - player_x).abs())
+                })
+                .map(|(entity, _)| entity)
+        });
+
+    for (mut autopilot, grounded) in &mut autopilots {
+        // Committed once down, even if the player moves on
+        if grounded.0
+            && autopilot
+                .target
+                .is_some_and(|target| land_pads.contains(target))
+        {
+            continue;
+        }
+
+        autopilot.target = player_pad;
+    }
+}
+
+/// Where a lander resting on the pad has its center.
+pub(super) fn landing_target((land_pad, pad_transform): (&LandPad, &GlobalTransform)) -> Vec2 {
+    let pad_center = pad_transform.translation().truncate();
+    Vec2::new(
+        pad_center.x + land_pad.clear_offset,
+        pad_center.y - LAND_PAD_SENSOR_HEIGHT / 2.0 + LANDER_SIZE.y as f32 / 2.0,
+    )
+}
+
+pub(super) fn autopilot_system(
+    mut autopilots: Query<(
+        &Autopilot,
+        &mut ControlInput,
+        &Transform,
+        &LinearVelocity,
+        &AngularVelocity,
+        &Mass,
+    )>,
+    land_pads: Query<(&LandPad, &GlobalTransform)>,
+    terrain: TerrainQuery,
+) {
+    let mut rng = rand::rng();
+
+    for (autopilot, mut control, transform, linear_velocity, angular_velocity, mass) in
+        &mut autopilots
+    {
+        let Some(pad) = autopilot.target.and_then(|pad| land_pads.get(pad).ok()) else {
+            *control = ControlInput::default();
+            continue;
+        };
+
+        let target = landing_target(pad);
+
+        let state = autopilot::LanderState {
+            position: transform.translation.truncate(),
+            velocity: linear_velocity.0,
+            angle: transform.rotation.to_euler(EulerRot::XYZ).2,
+            angular_velocity: angular_velocity.0,
+            max_acceleration: THRUST / mass.0,
+            gravity: -GRAVITY.y,
+            ground_height: terrain.height_at(transform.translation.x),
+        };
+
+        let mut command = autopilot::autoland(&state, target);
+
+        let noise = 1.0 - autopilot.skill;
+        if noise > 0.0 {
+            command.rotation =
+                (command.rotation + rng.random_range(-noise..=noise)).clamp(-1.0, 1.0);
+        }
+
+        *control = command;
+    }
+}
+
+fn rival_landing_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut rival: Single<
+        (
+            Entity,
+            &Grounded,
+            &LinearVelocity,
+            &AngularVelocity,
+            &Transform,
+            &mut RivalSettleTimer,
+            &LandingZone,
+            &Autopilot,
+        ),
+        With<Rival>,
+    >,
+    time_passed: Res<TimePassed>,
+    rules: Res<GameRules>,
+    mut rival_status: ResMut<RivalStatus>,
+) {
+    if *rival_status != RivalStatus::Flying {
+        return;
+    }
+
+    let stable = stability_issue(
+        rival.1.0,
+        rival.2.0,
+        rival.3.0,
+        rival.4,
+        &rules.win_tolerances,
+    )
+    .is_none();
+
+    // Only settling on the pad it is racing the player for counts
+    let on_contested_pad = rival.6.0.is_some() && rival.6.0 == rival.7.target;
+
+    if !stable || !on_contested_pad {
+        rival.5.0.reset();
+        return;
+    }
+
+    rival.5.0.tick(time.delta());
+    if rival.5.0.just_finished() {
+        *rival_status = RivalStatus::Landed(time_passed.0);
+        commands
+            .entity(rival.0)
+            .remove::<Autopilot>()
+            .insert(ControlInput::default());
+    }
+}
+
+fn rival_crash_observer(
+    event: On<CollisionStart>,
+    mut commands: Commands,
+    mut rival: Single<(Entity, &mut PlayerState), With<Rival>>,
+    ground_query: Query<(), With<Ground>>,
+    collisions: Collisions,
+    rival_status: Option<ResMut<RivalStatus>>,
+    mut audio_cues: MessageWriter<AudioCue>,
+) {
+    let Some(mut rival_status) = rival_status else {
+        return;
+    };
+
+    if *rival_status != RivalStatus::Flying || ground_query.get(event.collider2).is_err() {
+        return;
+    }
+
+    let impact_impulse_magnitude: f32 = collisions
+        .collisions_with(rival.0)
+        .map(|contact_pair| contact_pair.total_normal_impulse_magnitude())
+        .sum();
+
+    if impact_impulse_magnitude > SAFE_LANDING_IMPULSE_MAGNITUDE {
+        *rival_status = RivalStatus::Crashed;
+        *rival.1 = PlayerState::Crashed;
+        audio_cues.write(AudioCue::Crash(rival.0));
+        commands
+            .entity(rival.0)
+            .remove::<Autopilot>()
+            .insert((ControlInput::default(), LockedAxes::ALL_LOCKED));
+    }
+}
+
+fn cleanup_rival(mut commands: Commands) {
+    commands.remove_resource::<RivalStatus>();
+}
@@ -0,0 +1,77 @@
+//! Base and outpost pads refueling and repairing the lander resting on them.
+
+use super::{outcome::on_base_pad, player::FuelLeak, world::LandPad, *};
+
+const LEAK_REPAIR_DURATION: f32 = 5.0;
+const BASE_REFUEL_RATE: f32 = 50.0; // fuel units per second
+const BASE_RCS_REFUEL_RATE: f32 = 20.0; // units per second
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        base_servicing_system.run_if(in_state(GamePhase::Running)),
+    );
+}
+
+/// Progress of the base pad services the lander is currently receiving.
+#[derive(Component, Default)]
+pub(super) struct Servicing {
+    pub(super) leak_repair: f32,
+    pub(super) refueled: f32,
+}
+
+fn base_servicing_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player: Single<
+        (
+            Entity,
+            &Grounded,
+            &LinearVelocity,
+            &LandingZone,
+            &mut Fuel,
+            Has<FuelLeak>,
+            Option<&mut Servicing>,
+            Option<&mut RcsFuel>,
+        ),
+        With<Player>,
+    >,
+    land_pads: Query<&LandPad>,
+) {
+    let entity = player.0;
+
+    let docked = player.1.0 && player.2.0.length() < 5.0 && on_base_pad(player.3, &land_pads);
+
+    if !docked {
+        if player.6.is_some() {
+            commands.entity(entity).remove::<Servicing>();
+        }
+        return;
+    }
+
+    let leaking = player.5;
+    let fuel = player.4.0;
+
+    let Some(servicing) = player.6.as_mut() else {
+        commands.entity(entity).insert(Servicing::default());
+        return;
+    };
+
+    if leaking {
+        servicing.leak_repair += time.delta_secs() / LEAK_REPAIR_DURATION;
+        if servicing.leak_repair >= 1.0 {
+            servicing.leak_repair = 0.0;
+            commands.entity(entity).remove::<FuelLeak>();
+        }
+    } else if fuel < MAX_FUEL {
+        servicing.refueled += BASE_REFUEL_RATE * time.delta_secs();
+        let whole_units = servicing.refueled.floor();
+        servicing.refueled -= whole_units;
+        player.4.0 = (fuel + whole_units as u32).min(MAX_FUEL);
+    }
+
+    if let Some(rcs_fuel) = player.7.as_mut() {
+        rcs_fuel.amount =
+            (rcs_fuel.amount + BASE_RCS_REFUEL_RATE * time.delta_secs()).min(MAX_RCS_FUEL);
+    }
+}
@@ -0,0 +1,277 @@
+//! Supply pods dropped near the lander now and then, paying out fuel and
+//! credits to a lander that sets down next to one before it goes dark.
+
+use super::{audio::AudioCue, credits::Credits, player::ExhaustParticle, world::TerrainQuery, *};
+
+const SUPPLY_DROP_INTERVAL: f32 = 90.0; // average seconds between drops
+const SUPPLY_DROP_MIN_DISTANCE: f32 = 150.0; // from the lander, sideways
+const SUPPLY_DROP_MAX_DISTANCE: f32 = 400.0;
+const SUPPLY_DROP_HEIGHT: f32 = 600.0; // above the ground under the drop point
+const SUPPLY_DROP_TIME_LIMIT: f32 = 60.0; // seconds from touchdown to claim the pod
+
+const SUPPLY_POD_SIZE: Vec2 = Vec2::new(8.0, 12.0);
+const SUPPLY_POD_COLOR: Color = Color::srgb(0.9, 0.9, 0.8);
+const SUPPLY_POD_DERELICT_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const SUPPLY_POD_MARKER_COLOR: Color = Color::srgb(0.3, 1.0, 0.6);
+const SUPPLY_POD_CHUTE_RADIUS: f32 = 14.0;
+const SUPPLY_POD_CHUTE_HEIGHT: f32 = 20.0; // of the canopy above the top of the pod
+const SUPPLY_POD_DESCENT_SPEED: f32 = 40.0; // under the chute
+const SUPPLY_POD_BURN_ALTITUDE: f32 = 120.0; // height above the ground the chute is cut and the retro-burn lights at
+const SUPPLY_POD_TOUCHDOWN_SPEED: f32 = 4.0;
+const SUPPLY_POD_PUFF_INTERVAL: f32 = 0.03;
+const SUPPLY_POD_PUFF_LIFETIME: f32 = 0.4;
+const SUPPLY_POD_PUFF_SPEED: f32 = 60.0;
+
+const SUPPLY_POD_CLAIM_RADIUS: f32 = 60.0; // sideways distance the lander has to set down within
+const SUPPLY_POD_FUEL: u32 = 300;
+const SUPPLY_POD_CREDITS: u32 = 150;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), setup_supply_drops)
+        .add_systems(
+            Update,
+            (
+                supply_drop_spawn_system.run_if(supply_drops_enabled),
+                supply_pod_flight_system,
+                supply_pod_expiry_system,
+                supply_pod_claim_system,
+            )
+                .chain()
+                .run_if(in_state(GamePhase::Running)),
+        )
+        .add_systems(
+            Update,
+            supply_pod_marker_system.run_if(in_state(GameState::Game)),
+        )
+        .add_systems(OnExit(GameState::Game), cleanup_supply_drops);
+}
+
+/// Supply pod on its way down or waiting to be claimed.
+#[derive(Component)]
+pub(super) struct SupplyPod {
+    pub(super) landed: bool,
+    burning: bool,
+    puff_timer: Timer,
+    /// Time left to claim the pod, running once it lands.
+    pub(super) expiry: Timer,
+}
+
+/// Supply pod nobody claimed in time, left dark where it landed.
+#[derive(Component)]
+pub(super) struct DerelictPod;
+
+#[derive(Resource)]
+struct SupplyDropTimer(Timer);
+
+fn supply_drops_enabled(rules: Res<GameRules>) -> bool {
+    !rules.practice
+}
+
+fn setup_supply_drops(mut commands: Commands) {
+    commands.insert_resource(SupplyDropTimer(Timer::from_seconds(
+        SUPPLY_DROP_INTERVAL,
+        TimerMode::Once,
+    )));
+}
+
+fn cleanup_supply_drops(mut commands: Commands) {
+    commands.remove_resource::<SupplyDropTimer>();
+}
+
+fn supply_drop_spawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut drop_timer: ResMut<SupplyDropTimer>,
+    player: Single<&Transform, With<Player>>,
+    pods: Query<(), With<SupplyPod>>,
+    terrain: TerrainQuery,
+    mut audio_cues: MessageWriter<AudioCue>,
+) {
+    drop_timer.0.tick(time.delta());
+    if !drop_timer.0.is_finished() {
+        return;
+    }
+
+    let mut rng = rand::rng();
+
+    let next_interval = SUPPLY_DROP_INTERVAL * rng.random_range(0.5..1.5);
+    drop_timer.0 = Timer::from_seconds(next_interval, TimerMode::Once);
+
+    // One pod at a time
+    if !pods.is_empty() {
+        return;
+    }
+
+    let side = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+    let x = player.translation.x
+        + side * rng.random_range(SUPPLY_DROP_MIN_DISTANCE..SUPPLY_DROP_MAX_DISTANCE);
+    let y = terrain.height_at(x) + SUPPLY_DROP_HEIGHT;
+
+    commands.spawn((
+        DespawnOnExit(GameState::Game),
+        SupplyPod {
+            landed: false,
+            burning: false,
+            puff_timer: Timer::from_seconds(SUPPLY_POD_PUFF_INTERVAL, TimerMode::Repeating),
+            expiry: Timer::from_seconds(SUPPLY_DROP_TIME_LIMIT, TimerMode::Once),
+        },
+        Sprite::from_color(SUPPLY_POD_COLOR, SUPPLY_POD_SIZE),
+        Transform::from_translation(Vec3::new(x, y, -0.4)),
+    ));
+
+    audio_cues.write(AudioCue::Warning);
+}
+
+/// Drops pods under their chute, then brakes them with a retro-burn close to the
+/// ground.
+fn supply_pod_flight_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    terrain: TerrainQuery,
+    mut pods: Query<(&mut SupplyPod, &mut Transform)>,
+) {
+    let dt = time.delta_secs();
+    let mut rng = rand::rng();
+
+    for (mut pod, mut transform) in &mut pods {
+        if pod.landed {
+            pod.expiry.tick(time.delta());
+            continue;
+        }
+
+        let height =
+            terrain.ground_distance(transform.translation.truncate()) - SUPPLY_POD_SIZE.y / 2.0;
+
+        pod.burning = height < SUPPLY_POD_BURN_ALTITUDE;
+        let speed = if pod.burning {
+            let progress = (height / SUPPLY_POD_BURN_ALTITUDE).max(0.0);
+            SUPPLY_POD_TOUCHDOWN_SPEED
+                + (SUPPLY_POD_DESCENT_SPEED - SUPPLY_POD_TOUCHDOWN_SPEED) * progress
+        } else {
+            SUPPLY_POD_DESCENT_SPEED
+        };
+
+        if speed * dt >= height {
+            transform.translation.y -= height;
+            pod.landed = true;
+            pod.burning = false;
+            continue;
+        }
+        transform.translation.y -= speed * dt;
+
+        if pod.burning {
+            pod.puff_timer.tick(time.delta());
+            let nozzle = transform.translation - Vec3::new(0.0, SUPPLY_POD_SIZE.y / 2.0, 0.1);
+            for _ in 0..pod.puff_timer.times_finished_this_tick() {
+                commands.spawn((
+                    DespawnOnExit(GameState::Game),
+                    ExhaustParticle {
+                        velocity: Vec2::new(
+                            rng.random_range(-0.2..0.2) * SUPPLY_POD_PUFF_SPEED,
+                            -SUPPLY_POD_PUFF_SPEED,
+                        ),
+                        lifetime: Timer::from_seconds(SUPPLY_POD_PUFF_LIFETIME, TimerMode::Once),
+                    },
+                    Sprite::from_color(Color::WHITE, Vec2::splat(1.5)),
+                    Transform::from_translation(nozzle),
+                ));
+            }
+        }
+    }
+}
+
+fn supply_pod_expiry_system(
+    mut commands: Commands,
+    mut pods: Query<(Entity, &SupplyPod, &mut Sprite)>,
+) {
+    for (entity, pod, mut sprite) in &mut pods {
+        if pod.expiry.is_finished() {
+            sprite.color = SUPPLY_POD_DERELICT_COLOR;
+            commands
+                .entity(entity)
+                .remove::<SupplyPod>()
+                .insert(DerelictPod);
+        }
+    }
+}
+
+/// Hands the pod's fuel and credits to a lander resting next to it.
+fn supply_pod_claim_system(
+    mut commands: Commands,
+    mut player: Single<
+        (
+            Entity,
+            &Transform,
+            &LinearVelocity,
+            &Grounded,
+            &PlayerState,
+            &mut Fuel,
+            Option<&mut RcsFuel>,
+        ),
+        With<Player>,
+    >,
+    pods: Query<(Entity, &SupplyPod, &Transform), Without<Player>>,
+    rules: Res<GameRules>,
+    mut credits: ResMut<Credits>,
+    mut audio_cues: MessageWriter<AudioCue>,
+) {
+    if !player.3.0
+        || *player.4 == PlayerState::Crashed
+        || player.2.0.length() > rules.win_tolerances.max_speed
+    {
+        return;
+    }
+
+    for (entity, pod, transform) in &pods {
+        if !pod.landed
+            || (transform.translation.x - player.1.translation.x).abs() > SUPPLY_POD_CLAIM_RADIUS
+        {
+            continue;
+        }
+
+        player.5.0 = (player.5.0 + SUPPLY_POD_FUEL).min(MAX_FUEL);
+        if let Some(rcs_fuel) = player.6.as_mut() {
+            rcs_fuel.amount = MAX_RCS_FUEL;
+        }
+        credits.0 += SUPPLY_POD_CREDITS;
+
+        commands.entity(entity).despawn();
+        audio_cues.write(AudioCue::SupplyClaimed(player.0));
+    }
+}
+
+/// Draws the chute over falling pods, and a marker over landed ones.
+fn supply_pod_marker_system(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    pods: Query<(&SupplyPod, &Transform)>,
+) {
+    for (pod, transform) in &pods {
+        let top = transform.translation.truncate() + Vec2::Y * SUPPLY_POD_SIZE.y / 2.0;
+
+        if !pod.landed && !pod.burning {
+            let center = top + Vec2::Y * SUPPLY_POD_CHUTE_HEIGHT;
+            let canopy = (0..=8).map(|i| {
+                let angle = PI * i as f32 / 8.0;
+                center + Vec2::new(angle.cos(), angle.sin() * 0.5) * SUPPLY_POD_CHUTE_RADIUS
+            });
+            gizmos.linestrip_2d(canopy, SUPPLY_POD_COLOR);
+            for side in [-1.0, 1.0] {
+                gizmos.line_2d(
+                    top,
+                    center + Vec2::X * side * SUPPLY_POD_CHUTE_RADIUS,
+                    SUPPLY_POD_COLOR,
+                );
+            }
+        }
+
+        if pod.landed {
+            // Bobbing chevron, quicker as the claim window runs out
+            let urgency = 1.0 + 3.0 * pod.expiry.fraction();
+            let bob = (time.elapsed_secs() * urgency * 2.0 * PI).sin() * 3.0;
+            let tip = top + Vec2::Y * (12.0 + bob);
+            gizmos.line_2d(tip, tip + Vec2::new(-6.0, 6.0), SUPPLY_POD_MARKER_COLOR);
+            gizmos.line_2d(tip, tip + Vec2::new(6.0, 6.0), SUPPLY_POD_MARKER_COLOR);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Distant autopilot traffic landing on far-off pads, for a sense of a busy
+//! surface.
+
+use super::{
+    camera::Culled,
+    rival::landing_target,
+    world::{LandPad, TerrainQuery},
+    *,
+};
+
+const TRAFFIC_SPAWN_INTERVAL: f32 = 20.0; // average seconds between traffic landers
+const TRAFFIC_MIN_DISTANCE: f32 = 800.0; // only pads at least this far from the player get traffic
+const TRAFFIC_SPAWN_HEIGHT: f32 = 400.0; // above the target pad
+const TRAFFIC_DESPAWN_DISTANCE: f32 = 2400.0;
+const TRAFFIC_LANDED_LINGER: f32 = 5.0;
+const TRAFFIC_COLOR: Color = Color::srgb(0.45, 0.45, 0.45);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::Game), setup_traffic)
+        .add_systems(
+            Update,
+            (traffic_spawn_system, traffic_flight_system)
+                .chain()
+                .run_if(in_state(GamePhase::Running)),
+        )
+        .add_systems(OnExit(GameState::Game), cleanup_traffic);
+}
+
+/// Distant, non-interactive lander flown kinematically by the autopilot.
+#[derive(Component)]
+pub(super) struct TrafficLander {
+    target: Entity,
+    velocity: Vec2,
+    angular_velocity: f32,
+    landed_for: f32,
+}
+
+#[derive(Resource)]
+struct TrafficSpawnTimer(Timer);
+
+fn traffic_spawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut spawn_timer: ResMut<TrafficSpawnTimer>,
+    player: Single<&Transform, With<Player>>,
+    land_pads: Query<(Entity, &GlobalTransform), With<LandPad>>,
+    lander_sprite: Res<LanderSprite>,
+) {
+    spawn_timer.0.tick(time.delta());
+    if !spawn_timer.0.is_finished() {
+        return;
+    }
+
+    let mut rng = rand::rng();
+
+    let next_interval = TRAFFIC_SPAWN_INTERVAL * rng.random_range(0.5..1.5);
+    spawn_timer.0 = Timer::from_seconds(next_interval, TimerMode::Once);
+
+    let player_x = player.translation.x;
+    let far_pads: Vec<(Entity, Vec2)> = land_pads
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation().truncate()))
+        .filter(|(_, pad)| (pad.x - player_x).abs() >= TRAFFIC_MIN_DISTANCE)
+        .collect();
+
+    if far_pads.is_empty() {
+        return;
+    }
+
+    let (target, pad) = far_pads[rng.random_range(0..far_pads.len())];
+
+    let mut sprite = Sprite::from_atlas_image(
+        lander_sprite.texture.clone(),
+        TextureAtlas {
+            layout: lander_sprite.layout.clone(),
+            index: 0,
+        },
+    );
+    sprite.color = TRAFFIC_COLOR;
+
+    commands.spawn((
+        DespawnOnExit(GameState::Game),
+        TrafficLander {
+            target,
+            velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            landed_for: 0.0,
+        },
+        sprite,
+        Transform::from_translation(Vec3::new(
+            pad.x + rng.random_range(-200.0..200.0),
+            pad.y + TRAFFIC_SPAWN_HEIGHT,
+            -0.5,
+        )),
+    ));
+}
+
+fn traffic_flight_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut traffic: Query<(
+        Entity,
+        &mut TrafficLander,
+        &mut Transform,
+        &mut Sprite,
+        Has<Culled>,
+    )>,
+    player: Single<&Transform, (With<Player>, Without<TrafficLander>)>,
+    land_pads: Query<(&LandPad, &GlobalTransform)>,
+    terrain: TerrainQuery,
+) {
+    let dt = time.delta_secs();
+    let max_acceleration = THRUST / (DRY_LANDER_MASS + MAX_FUEL as f32 * FUEL_MASS_FACTOR / 2.0);
+
+    for (entity, mut lander, mut transform, mut sprite, culled) in &mut traffic {
+        let Ok(pad) = land_pads.get(lander.target) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        if (transform.translation.x - player.translation.x).abs() > TRAFFIC_DESPAWN_DISTANCE {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let target = landing_target(pad);
+
+        let position = transform.translation.truncate();
+
+        if position.y <= target.y {
+            transform.translation.y = target.y;
+            lander.velocity = Vec2::ZERO;
+            lander.angular_velocity = 0.0;
+            lander.landed_for += dt;
+            if lander.landed_for >= TRAFFIC_LANDED_LINGER {
+                commands.entity(entity).despawn();
+            }
+            if !culled {
+                sprite.texture_atlas.as_mut().unwrap().index = 0;
+            }
+            continue;
+        }
+
+        let angle = transform.rotation.to_euler(EulerRot::XYZ).2;
+
+        let state = autopilot::LanderState {
+            position,
+            velocity: lander.velocity,
+            angle,
+            angular_velocity: lander.angular_velocity,
+            max_acceleration,
+            gravity: -GRAVITY.y,
+            ground_height: terrain.height_at(position.x),
+        };
+
+        let command = autopilot::autoland(&state, target);
+
+        lander.angular_velocity += command.rotation * ROTATION_THRUST * dt;
+        let angle = angle + lander.angular_velocity * dt;
+
+        let thrust_direction = Vec2::new(-angle.sin(), angle.cos());
+        let acceleration = thrust_direction * command.throttle * max_acceleration + GRAVITY;
+        lander.velocity += acceleration * dt;
+
+        transform.translation += (lander.velocity * dt).extend(0.0);
+        transform.rotation = Quat::from_rotation_z(angle);
+
+        if !culled {
+            sprite.texture_atlas.as_mut().unwrap().index =
+                if command.throttle > 0.0 { 1 } else { 0 };
+        }
+    }
+}
+
+fn setup_traffic(mut commands: Commands) {
+    commands.insert_resource(TrafficSpawnTimer(Timer::from_seconds(
+        TRAFFIC_SPAWN_INTERVAL,
+        TimerMode::Once,
+    )));
+}
+
+fn cleanup_traffic(mut commands: Commands) {
+    commands.remove_resource::<TrafficSpawnTimer>();
+}
@@ -0,0 +1,682 @@
+//! The world around the lander: terrain streamed in chunks around the camera,
+//! and the pads on it.
+
+use super::{
+    camera::Culled,
+    outcome::{player_entered_landing_zone, player_exited_landing_zone},
+    outposts::Outposts,
+    *,
+};
+
+const CHUNK_BUFFER_OUTSIDE_VIEWPORT_COUNT: i32 = 3;
+const PAD_SEARCH_CHUNKS: i32 = 200; // how far right of the start standard pads are looked for
+
+const PAD_LIGHTS_PER_SIDE: usize = 4;
+const PAD_LIGHT_SIZE: f32 = 2.0;
+const PAD_LIGHT_COLOR: Color = Color::srgb(1.0, 0.8, 0.3);
+const PAD_LIGHT_SEQUENCE_TIME: f32 = 1.2; // seconds for the sequence to run from the edges to the center
+const PAD_LIGHT_FLASH: f32 = 0.3; // fraction of the sequence each light takes to fade after blinking
+const PAD_LIGHT_DIM: f32 = 0.15; // alpha of an unlit light
+const PAD_BEACON_HEIGHT: f32 = 600.0;
+const PAD_BEACON_WIDTH: f32 = 3.0;
+const PAD_BEACON_COLOR: Color = Color::srgb(0.6, 0.8, 1.0);
+const PAD_BEACON_PULSE_PERIOD: f32 = 2.5; // seconds
+const PAD_BEACON_MIN_ALPHA: f32 = 0.04;
+const PAD_BEACON_MAX_ALPHA: f32 = 0.15;
+const PAD_HAZARD_LIGHT_COLOR: Color = Color::srgb(1.0, 0.2, 0.1); // lights under a pad boulder
+const PAD_BOULDER_HEIGHT: f32 = 8.0;
+const PAD_BOULDER_COLOR: Color = Color::srgb(0.5, 0.45, 0.4);
+
+const WRECK_COLOR: Color = Color::srgb(0.4, 0.4, 0.4);
+
+const PAD_MULTIPLIER_DECAY_DURATION: f32 = 300.0; // seconds until a pad's bonus over x1.0 is gone
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            terrain_chunk_system.in_set(FlightSystems::Terrain),
+            pad_multiplier_decay_system.run_if(pad_multiplier_decay_enabled),
+        )
+            .run_if(in_state(GamePhase::Running)),
+    )
+    .add_systems(Update, pad_lights_system.run_if(in_state(GameState::Game)))
+    .add_systems(OnEnter(GameState::Game), apply_physics_quality);
+}
+
+#[derive(Component)]
+pub(super) struct Ground;
+
+#[derive(Component)]
+struct TerrainChunk {
+    x_origin: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) enum LandPadKind {
+    Standard,
+    /// Services the lander while it rests on the pad instead of ending the run.
+    Base,
+    /// Pad the player bought this run, serviced like a base.
+    Outpost,
+}
+
+#[derive(Component)]
+pub(super) struct LandPad {
+    pub(super) kind: LandPadKind,
+    pub(super) chunk_origin: i32,
+    pub(super) width: f32,
+    pub(super) base_multiplier: f32,
+    pub(super) score_multiplier: f32,
+    /// Offset from the pad center to the middle of the stretch that can be
+    /// landed on, away from any boulder.
+    pub(super) clear_offset: f32,
+    /// Width of the stretch that can be landed on.
+    pub(super) clear_width: f32,
+}
+
+#[derive(Component)]
+pub(super) struct LandPadLabel;
+
+/// Edge light of a pad, blinking in sequence towards the pad center.
+#[derive(Component)]
+pub(super) struct PadLight {
+    /// Place in the sequence, 0.0 at the pad edges and approaching 1.0 at the
+    /// center.
+    step: f32,
+    color: Color,
+}
+
+/// Boulder on a hazard pad. Touching it scrubs the landing.
+#[derive(Component)]
+pub(super) struct PadObstruction;
+
+/// Vertical light beam marking a pad from afar.
+#[derive(Component)]
+pub(super) struct PadBeacon;
+
+#[derive(Resource)]
+pub(super) struct Terrain(pub(super) TerrainGenerator);
+
+#[derive(Resource)]
+pub(super) struct TerrainMaterial {
+    pub(super) regolith: Handle<ColorMaterial>,
+    pub(super) ice: Handle<ColorMaterial>,
+    pub(super) soft_regolith: Handle<ColorMaterial>,
+}
+
+impl TerrainMaterial {
+    pub(super) fn get(&self, surface: Surface) -> &Handle<ColorMaterial> {
+        match surface {
+            Surface::Regolith => &self.regolith,
+            Surface::Ice => &self.ice,
+            Surface::SoftRegolith => &self.soft_regolith,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct OccluderMaterial(pub(super) Handle<ColorMaterial>);
+
+/// Terrain seen this run, for the map view.
+#[derive(Resource, Default)]
+pub(super) struct ExploredTerrain {
+    /// Surface points in world space, keyed by chunk origin.
+    pub(super) profiles: BTreeMap<i32, Vec<Vec2>>,
+    /// Pad surface centers and kinds, keyed by chunk origin.
+    pub(super) pads: BTreeMap<i32, (Vec2, LandPadKind)>,
+}
+
+/// Applies the configured solver quality to the physics for the run.
+fn apply_physics_quality(
+    rules: Res<GameRules>,
+    mut substeps: ResMut<SubstepCount>,
+    mut solver_config: ResMut<SolverConfig>,
+) {
+    substeps.0 = rules.physics_quality.substeps;
+    solver_config.restitution_iterations = rules.physics_quality.restitution_iterations;
+}
+
+/// Contact coefficients for a surface material.
+pub(super) fn surface_physics(material: SurfaceMaterial) -> (Restitution, Friction) {
+    let combine_rule = if material.dominant {
+        CoefficientCombine::Min
+    } else {
+        CoefficientCombine::Average
+    };
+
+    (
+        Restitution::new(material.restitution).with_combine_rule(combine_rule),
+        Friction::new(material.friction).with_combine_rule(combine_rule),
+    )
+}
+
+/// Standard pads of the seed right of the start, in order, with the world
+/// position of their surface center.
+pub(super) fn standard_pads(
+    terrain_generator: &TerrainGenerator,
+) -> impl Iterator<Item = (Vec2, PadSite)> + '_ {
+    (0..PAD_SEARCH_CHUNKS).filter_map(|chunk| {
+        let x_origin = (chunk as f32) * CHUNK_WIDTH;
+        let pad = terrain_generator.generate_chunk(x_origin).pad?;
+        (!pad.base).then(|| {
+            (
+                pad.center + Vec2::new(x_origin + CHUNK_WIDTH / 2.0, 0.0),
+                pad,
+            )
+        })
+    })
+}
+
+/// Ground queries answered from the generated chunks, so systems that need the
+/// surface under a point don't each sample the terrain or cast rays.
+#[derive(SystemParam)]
+pub(super) struct TerrainQuery<'w> {
+    pub(super) terrain: Res<'w, Terrain>,
+    pub(super) explored_terrain: Res<'w, ExploredTerrain>,
+}
+
+impl TerrainQuery<'_> {
+    /// Height of the ground at world `x`, pads included. Where no chunk has been
+    /// generated yet this is the unflattened terrain surface.
+    pub(super) fn height_at(&self, x: f32) -> f32 {
+        match self.segment_at(x) {
+            Some((a, b)) => a.y + (b.y - a.y) * (x - a.x) / (b.x - a.x),
+            // Chunks are drawn half a chunk to the right of the terrain they sample.
+            None => self.terrain.0.sample_height(x - CHUNK_WIDTH / 2.0),
+        }
+    }
+
+    /// Rise over run of the ground at world `x`.
+    pub(super) fn slope_at(&self, x: f32) -> f32 {
+        match self.segment_at(x) {
+            Some((a, b)) => (b.y - a.y) / (b.x - a.x),
+            None => {
+                let step = CHUNK_GRANULARITY as f32 / 2.0;
+                (self.height_at(x + step) - self.height_at(x - step)) / (2.0 * step)
+            }
+        }
+    }
+
+    /// Distance from `point` straight down to the ground, negative below it.
+    pub(super) fn ground_distance(&self, point: Vec2) -> f32 {
+        point.y - self.height_at(point.x)
+    }
+
+    /// Surface points of the generated chunk on either side of world `x`.
+    fn segment_at(&self, x: f32) -> Option<(Vec2, Vec2)> {
+        let profile = self.explored_terrain.profiles.get(&chunk_origin_at(x))?;
+        let i = profile.partition_point(|point| point.x <= x);
+        (i > 0 && i < profile.len()).then(|| (profile[i - 1], profile[i]))
+    }
+}
+
+fn terrain_mesh(heights: &[f32], width: f32) -> Mesh {
+    let mut positions = Vec::new();
+    let mut indices = Vec::new();
+
+    for (i, &h) in heights.iter().enumerate() {
+        let x = i as f32 * width;
+
+        positions.push([x, 0.0, 0.0]);
+        positions.push([x, h, 0.0]);
+    }
+
+    for i in 0..heights.len() - 1 {
+        let base = (i * 2) as u32;
+
+        indices.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_indices(Indices::U32(indices));
+
+    mesh
+}
+
+fn create_terrain_chunk(
+    commands: &mut Commands,
+    x_origin: f32,
+    terrain: &Terrain,
+    terrain_material: &TerrainMaterial,
+    occluder_material: &Handle<ColorMaterial>,
+    font: &MainFont,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    lander_sprite: &LanderSprite,
+    crash_sites: &[Transform],
+    outposts: &Outposts,
+    explored_terrain: &mut ExploredTerrain,
+) {
+    let chunk_data = terrain.0.generate_chunk(x_origin);
+
+    let land_pad = chunk_data.pad.map(|pad| {
+        let kind = if pad.base {
+            LandPadKind::Base
+        } else if outposts.0.contains_key(&(x_origin as i32)) {
+            LandPadKind::Outpost
+        } else {
+            LandPadKind::Standard
+        };
+        (pad.center, pad.width, pad.multiplier, kind, pad.hazard)
+    });
+
+    let ground_points = chunk_data.points(CHUNK_GRANULARITY);
+
+    let chunk_left = x_origin + CHUNK_WIDTH / 2.0;
+
+    explored_terrain
+        .profiles
+        .entry(x_origin as i32)
+        .or_insert_with(|| {
+            ground_points
+                .iter()
+                .map(|point| Vec2::new(point.x + chunk_left, point.y))
+                .collect()
+        });
+
+    if let Some(pad) = land_pad {
+        explored_terrain.pads.insert(
+            x_origin as i32,
+            (Vec2::new(pad.0.x + chunk_left, pad.0.y), pad.3),
+        );
+    }
+
+    let ground_mesh = meshes.add(Polyline2d::new(ground_points.clone()));
+
+    let params = terrain.0.params();
+    let ground_physics = surface_physics(params.surface_material(chunk_data.surface));
+
+    // The pad stretch gets its own collider, so it can have the pad material
+    let (ground_collider, pad_collider) = match chunk_data.pad {
+        Some(pad) => {
+            let (first, last) = pad.samples;
+            (
+                Collider::compound(vec![
+                    (
+                        Vec2::ZERO,
+                        0.0,
+                        Collider::polyline(ground_points[..=first].to_vec(), None),
+                    ),
+                    (
+                        Vec2::ZERO,
+                        0.0,
+                        Collider::polyline(ground_points[last..].to_vec(), None),
+                    ),
+                ]),
+                Some(Collider::polyline(
+                    ground_points[first..=last].to_vec(),
+                    None,
+                )),
+            )
+        }
+        None => (Collider::polyline(ground_points, None), None),
+    };
+
+    let occluder_mesh = meshes.add(terrain_mesh(&chunk_data.heights, CHUNK_GRANULARITY as f32));
+
+    let mut chunk = commands.spawn((
+        DespawnOnExit(GameState::Game),
+        Ground,
+        TerrainChunk { x_origin },
+        RigidBody::Static,
+        //Collider::heightfield(ground_heights, Vec2::new(1.0, 1.0)),
+        ground_collider, // TODO: should use heightfield or similar for performance
+        ground_physics,
+        Mesh2d(ground_mesh),
+        MeshMaterial2d(terrain_material.get(chunk_data.surface).clone()),
+        Transform::from_translation(Vec3::new(x_origin + CHUNK_WIDTH as f32 / 2.0, 0.0, 0.0)),
+    ));
+
+    chunk.with_child((
+        Mesh2d(occluder_mesh),
+        MeshMaterial2d(occluder_material.clone()),
+    ));
+
+    if let Some(pad_collider) = pad_collider {
+        chunk.with_child((Ground, pad_collider, surface_physics(params.pad_material)));
+    }
+
+    if let Some(pad_pos) = land_pad {
+        let (score_multiplier, label) = match pad_pos.3 {
+            LandPadKind::Standard => (pad_pos.2, format!("x{:.1}", pad_pos.2)),
+            LandPadKind::Base => (1.0, "BASE".to_string()),
+            LandPadKind::Outpost => (1.0, "OUTPOST".to_string()),
+        };
+
+        chunk.with_children(|parent| {
+            parent
+                .spawn((
+                    LandPad {
+                        kind: pad_pos.3,
+                        chunk_origin: x_origin as i32,
+                        width: pad_pos.1 as f32,
+                        base_multiplier: score_multiplier,
+                        score_multiplier,
+                        clear_offset: pad_pos
+                            .4
+                            .map_or(0.0, |hazard| hazard.clear_offset(pad_pos.1 as f32)),
+                        clear_width: pad_pos.4.map_or(pad_pos.1 as f32, |hazard| {
+                            hazard.clear_width(pad_pos.1 as f32)
+                        }),
+                    },
+                    RigidBody::Static,
+                    Sensor,
+                    CollisionEventsEnabled,
+                    Collider::rectangle(pad_pos.1 as f32, LAND_PAD_SENSOR_HEIGHT),
+                    Transform::from_translation(Vec3::new(
+                        pad_pos.0.x,
+                        pad_pos.0.y + LAND_PAD_SENSOR_HEIGHT / 2.0,
+                        0.0,
+                    )),
+                    Visibility::default(),
+                ))
+                .observe(player_entered_landing_zone)
+                .observe(player_exited_landing_zone)
+                .with_child((
+                    LandPadLabel,
+                    Text2d::new(label),
+                    font.text_font(TextStyle::HudLabel),
+                    TextLayout::new_with_justify(Justify::Center),
+                    TextColor(Color::WHITE),
+                    Transform::from_translation(Vec3::new(0.0, -24.0, 0.0)),
+                ))
+                .with_children(|pad| {
+                    spawn_pad_lights(pad, pad_pos.1 as f32, pad_pos.4);
+                    if let Some(PadHazard::Boulder { right, fraction }) = pad_pos.4 {
+                        spawn_pad_boulder(
+                            pad,
+                            pad_pos.1 as f32,
+                            right,
+                            fraction,
+                            surface_physics(params.regolith_material),
+                        );
+                    }
+                });
+        });
+    }
+
+    for crash_site in crash_sites {
+        let local_x = crash_site.translation.x - chunk_left;
+        if !(0.0..CHUNK_WIDTH).contains(&local_x) {
+            continue;
+        }
+
+        let mut sprite = Sprite::from_atlas_image(
+            lander_sprite.texture.clone(),
+            TextureAtlas {
+                layout: lander_sprite.layout.clone(),
+                index: 2,
+            },
+        );
+        sprite.color = WRECK_COLOR;
+
+        chunk.with_child((
+            Wreck,
+            sprite,
+            Transform {
+                translation: Vec3::new(local_x, crash_site.translation.y, -0.5),
+                rotation: crash_site.rotation,
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+/// Edge lights and beacon of a pad `width` wide, relative to its sensor. Lights
+/// follow a sloped surface, and turn red under a boulder.
+fn spawn_pad_lights(pad: &mut ChildSpawnerCommands, width: f32, hazard: Option<PadHazard>) {
+    let surface = -LAND_PAD_SENSOR_HEIGHT / 2.0;
+    let spacing = width / 2.0 / PAD_LIGHTS_PER_SIDE as f32;
+
+    for i in 0..PAD_LIGHTS_PER_SIDE {
+        let offset = width / 2.0 - i as f32 * spacing;
+        for side in [-1.0, 1.0] {
+            let x = side * offset;
+            let (height, color) = match hazard {
+                Some(PadHazard::Sloped(slope)) => (slope * x, PAD_LIGHT_COLOR),
+                Some(PadHazard::Boulder { right, fraction })
+                    if (x > 0.0) == right && offset >= width / 2.0 * (1.0 - fraction) =>
+                {
+                    (0.0, PAD_HAZARD_LIGHT_COLOR)
+                }
+                _ => (0.0, PAD_LIGHT_COLOR),
+            };
+
+            pad.spawn((
+                PadLight {
+                    step: i as f32 / PAD_LIGHTS_PER_SIDE as f32,
+                    color,
+                },
+                Sprite::from_color(color, Vec2::splat(PAD_LIGHT_SIZE)),
+                Transform::from_translation(Vec3::new(
+                    x,
+                    surface + height + PAD_LIGHT_SIZE / 2.0,
+                    0.5,
+                )),
+            ));
+        }
+    }
+
+    pad.spawn((
+        PadBeacon,
+        Sprite::from_color(
+            PAD_BEACON_COLOR.with_alpha(PAD_BEACON_MIN_ALPHA),
+            Vec2::new(PAD_BEACON_WIDTH, PAD_BEACON_HEIGHT),
+        ),
+        Transform::from_translation(Vec3::new(0.0, surface + PAD_BEACON_HEIGHT / 2.0, -1.0)),
+    ));
+}
+
+/// Boulder covering `fraction` of one end of a pad `width` wide, relative to its
+/// sensor.
+fn spawn_pad_boulder(
+    pad: &mut ChildSpawnerCommands,
+    width: f32,
+    right: bool,
+    fraction: f32,
+    physics: (Restitution, Friction),
+) {
+    let size = Vec2::new(width * fraction, PAD_BOULDER_HEIGHT);
+    let side = if right { 1.0 } else { -1.0 };
+
+    pad.spawn((
+        PadObstruction,
+        Ground,
+        Collider::rectangle(size.x, size.y),
+        physics,
+        Sprite::from_color(PAD_BOULDER_COLOR, size),
+        Transform::from_translation(Vec3::new(
+            side * (width - size.x) / 2.0,
+            (size.y - LAND_PAD_SENSOR_HEIGHT) / 2.0,
+            0.6,
+        )),
+    ));
+}
+
+/// Origin of the chunk under world `x`. Chunks are drawn half a chunk to the
+/// right of their origin, see [`TerrainQuery::height_at`].
+fn chunk_origin_at(x: f32) -> i32 {
+    (((x - CHUNK_WIDTH / 2.0) / CHUNK_WIDTH).floor() * CHUNK_WIDTH) as i32
+}
+
+/// Origins of the chunks to keep loaded around the chunk at `center_origin`.
+fn needed_chunk_origins(center_origin: i32) -> Vec<i32> {
+    const CHUNKS_IN_CAMERA_VIEWPORT: i32 = (CAMERA_VIEWPORT_WIDTH / CHUNK_WIDTH).ceil() as i32 + 2; // +2 for buffer on each side
+
+    // Same number of chunks on both sides of the center chunk
+    ((-CHUNK_BUFFER_OUTSIDE_VIEWPORT_COUNT - CHUNKS_IN_CAMERA_VIEWPORT / 2)
+        ..=(CHUNKS_IN_CAMERA_VIEWPORT / 2 + CHUNK_BUFFER_OUTSIDE_VIEWPORT_COUNT))
+        .map(|i| center_origin + (i * CHUNK_WIDTH as i32))
+        .collect()
+}
+
+fn terrain_chunk_system(
+    mut commands: Commands,
+    player: Single<&Transform, With<Player>>,
+    existing_chunks: Query<(Entity, &TerrainChunk)>,
+    terrain: Res<Terrain>,
+    terrain_material: Res<TerrainMaterial>,
+    occluder_material: Res<OccluderMaterial>,
+    font: Res<MainFont>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    lander_sprite: Res<LanderSprite>,
+    terrain_seed: Res<TerrainSeed>,
+    crash_sites: Res<CrashSites>,
+    outposts: Res<Outposts>,
+    mut explored_terrain: ResMut<ExploredTerrain>,
+) {
+    let crash_sites = crash_sites
+        .0
+        .get(&terrain_seed.0)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let needed_chunk_origins = needed_chunk_origins(chunk_origin_at(player.translation.x));
+
+    // Remove chunks that are no longer needed
+    for (entity, chunk) in existing_chunks.iter() {
+        let chunk_x_origin_i32 = chunk.x_origin as i32;
+        if !needed_chunk_origins.contains(&chunk_x_origin_i32) {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    let exisitng_chunk_origins: Vec<i32> = existing_chunks
+        .iter()
+        .map(|(_, chunk)| chunk.x_origin as i32)
+        .collect::<Vec<i32>>();
+
+    let chunks_to_add: Vec<f32> = needed_chunk_origins
+        .iter()
+        .cloned()
+        .filter_map(|x_origin| {
+            if !exisitng_chunk_origins.contains(&x_origin) {
+                Some(x_origin as f32)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<f32>>();
+
+    for x_origin in chunks_to_add {
+        create_terrain_chunk(
+            &mut commands,
+            x_origin,
+            &terrain,
+            &terrain_material,
+            &occluder_material.0,
+            &font,
+            &mut meshes,
+            &lander_sprite,
+            crash_sites,
+            &outposts,
+            &mut explored_terrain,
+        );
+    }
+}
+
+fn pad_lights_system(
+    time: Res<Time>,
+    mut lights: Query<(&PadLight, &mut Sprite), Without<Culled>>,
+    mut beacons: Query<&mut Sprite, (With<PadBeacon>, Without<PadLight>, Without<Culled>)>,
+) {
+    let elapsed = time.elapsed_secs();
+
+    let sequence = (elapsed / PAD_LIGHT_SEQUENCE_TIME).fract();
+    for (light, mut sprite) in &mut lights {
+        let since_lit = (sequence - light.step).rem_euclid(1.0);
+        let brightness = (1.0 - since_lit / PAD_LIGHT_FLASH).max(PAD_LIGHT_DIM);
+        sprite.color = light.color.with_alpha(brightness);
+    }
+
+    let pulse = 0.5 + 0.5 * (2.0 * PI * elapsed / PAD_BEACON_PULSE_PERIOD).sin();
+    let alpha = PAD_BEACON_MIN_ALPHA + (PAD_BEACON_MAX_ALPHA - PAD_BEACON_MIN_ALPHA) * pulse;
+    for mut sprite in &mut beacons {
+        sprite.color = PAD_BEACON_COLOR.with_alpha(alpha);
+    }
+}
+
+fn pad_multiplier_decay_enabled(rules: Res<GameRules>) -> bool {
+    rules.pad_multiplier_decay
+}
+
+fn decayed_pad_multiplier(base_multiplier: f32, time_passed: Duration) -> f32 {
+    let remaining = (1.0 - time_passed.as_secs_f32() / PAD_MULTIPLIER_DECAY_DURATION).max(0.0);
+    1.0 + (base_multiplier - 1.0) * remaining
+}
+
+fn pad_multiplier_decay_system(
+    time_passed: Res<TimePassed>,
+    mut land_pads: Query<(&mut LandPad, &Children)>,
+    mut labels: Query<&mut Text2d, (With<LandPadLabel>, Without<Culled>)>,
+) {
+    for (mut land_pad, children) in &mut land_pads {
+        if land_pad.kind != LandPadKind::Standard {
+            continue;
+        }
+
+        land_pad.score_multiplier = decayed_pad_multiplier(land_pad.base_multiplier, time_passed.0);
+
+        let label = format!("x{:.1}", land_pad.score_multiplier);
+        for &child in children {
+            if let Ok(mut text) = labels.get_mut(child)
+                && text.0 != label
+            {
+                text.0 = label.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: i32 = CHUNK_WIDTH as i32;
+
+    #[test]
+    fn chunk_origin_at_floors_negative_x() {
+        // Chunks are drawn from half a chunk right of their origin
+        let half = CHUNK_WIDTH / 2.0;
+
+        assert_eq!(chunk_origin_at(half), 0);
+        assert_eq!(chunk_origin_at(half - 0.5), -WIDTH);
+        assert_eq!(chunk_origin_at(0.0), -WIDTH);
+        assert_eq!(chunk_origin_at(-0.5), -WIDTH);
+        assert_eq!(chunk_origin_at(-half), -WIDTH);
+        assert_eq!(chunk_origin_at(-half - 0.5), -2 * WIDTH);
+        assert_eq!(chunk_origin_at(-3.0 * CHUNK_WIDTH + half), -3 * WIDTH);
+    }
+
+    #[test]
+    fn chunk_origin_at_round_trips_with_chunk_index() {
+        let generator = TerrainGenerator::new(0, TerrainParams::default());
+
+        for index in -50..50 {
+            let start = index as f32 * CHUNK_WIDTH + CHUNK_WIDTH / 2.0;
+            for offset in [0.0, 0.5, CHUNK_WIDTH / 2.0, CHUNK_WIDTH - 0.5] {
+                let origin = chunk_origin_at(start + offset);
+                assert_eq!(origin, index * WIDTH);
+                assert_eq!(generator.chunk_index(origin as f32), index as i64);
+            }
+        }
+    }
+
+    #[test]
+    fn needed_chunks_are_symmetric() {
+        for center in [0, WIDTH, -WIDTH, -7 * WIDTH] {
+            let origins = needed_chunk_origins(center);
+
+            assert_eq!(origins.len() % 2, 1);
+            assert_eq!(origins[origins.len() / 2], center);
+            for (left, right) in origins.iter().zip(origins.iter().rev()) {
+                assert_eq!(center - left, right - center);
+            }
+            assert!(origins.windows(2).all(|pair| pair[1] - pair[0] == WIDTH));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Where runs end, accumulated across every run on each terrain seed, to spot
+//! spots that keep catching players out.
+
+use crate::stats::{RunFinished, RunOutcome, data_dir};
+
+use bevy::prelude::*;
+
+use std::{collections::HashMap, fs};
+
+const HEATMAP_FILE_NAME: &str = "heatmap.txt";
+pub(crate) const HEATMAP_CELL_SIZE: f32 = 40.0; // world units per heatmap cell side
+
+const OVERLAY_KEY: KeyCode = KeyCode::F9;
+
+/// Runs that ended inside one heatmap cell.
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct HeatCell {
+    pub(crate) landings: u32,
+    pub(crate) crashes: u32,
+}
+
+/// Landing and crash counts per cell, keyed by terrain seed, persisted in the
+/// data directory.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct Heatmap(HashMap<u32, HashMap<IVec2, HeatCell>>);
+
+impl Heatmap {
+    /// Cells recorded on `seed`, keyed by cell coordinates.
+    pub(crate) fn cells(&self, seed: u32) -> Option<&HashMap<IVec2, HeatCell>> {
+        self.0.get(&seed)
+    }
+
+    fn record(&mut self, run: &RunFinished) {
+        let cell = (run.position / HEATMAP_CELL_SIZE).floor().as_ivec2();
+        let cell = self.0.entry(run.seed).or_default().entry(cell).or_default();
+        match run.outcome {
+            RunOutcome::Landed => cell.landings += 1,
+            RunOutcome::Crashed => cell.crashes += 1,
+            RunOutcome::Aborted | RunOutcome::Quit => {}
+        }
+    }
+
+    fn load() -> Self {
+        let mut heatmap = Heatmap::default();
+
+        let Ok(contents) = fs::read_to_string(data_dir().join(HEATMAP_FILE_NAME)) else {
+            return heatmap;
+        };
+
+        for line in contents.lines() {
+            let values: Vec<i64> = line
+                .split(',')
+                .filter_map(|value| value.trim().parse().ok())
+                .collect();
+            let &[seed, x, y, landings, crashes] = values.as_slice() else {
+                continue;
+            };
+            heatmap.0.entry(seed as u32).or_default().insert(
+                IVec2::new(x as i32, y as i32),
+                HeatCell {
+                    landings: landings as u32,
+                    crashes: crashes as u32,
+                },
+            );
+        }
+
+        heatmap
+    }
+
+    fn save(&self) {
+        let mut contents = String::new();
+        for (seed, cells) in &self.0 {
+            for (position, cell) in cells {
+                contents.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    seed, position.x, position.y, cell.landings, cell.crashes
+                ));
+            }
+        }
+
+        if let Err(error) = fs::create_dir_all(data_dir())
+            .and_then(|_| fs::write(data_dir().join(HEATMAP_FILE_NAME), contents))
+        {
+            warn!("Could not save heatmap: {}", error);
+        }
+    }
+}
+
+/// Whether the in-game heatmap overlay is shown.
+#[derive(Resource, Default)]
+pub(crate) struct HeatmapOverlay(pub(crate) bool);
+
+pub(crate) fn plugin(app: &mut App) {
+    app.insert_resource(Heatmap::load())
+        .init_resource::<HeatmapOverlay>()
+        .add_systems(Update, (record_heatmap_system, overlay_toggle_system));
+}
+
+fn record_heatmap_system(
+    mut runs_finished: MessageReader<RunFinished>,
+    mut heatmap: ResMut<Heatmap>,
+) {
+    let mut changed = false;
+    for run in runs_finished.read() {
+        if !run.practice && matches!(run.outcome, RunOutcome::Landed | RunOutcome::Crashed) {
+            heatmap.record(run);
+            changed = true;
+        }
+    }
+
+    if changed {
+        heatmap.save();
+    }
+}
+
+fn overlay_toggle_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<HeatmapOverlay>,
+) {
+    if keyboard_input.just_pressed(OVERLAY_KEY) {
+        overlay.0 = !overlay.0;
+    }
+}
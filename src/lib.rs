@@ -1,5 +1,14 @@
+mod capture;
+mod crash_report;
 mod game;
+mod heatmap;
 mod main_menu;
+mod run_log;
+mod stats;
+mod synth;
+mod telemetry;
+mod terrain;
+mod typography;
 
 use avian2d::PhysicsPlugins;
 use bevy::prelude::*;
@@ -14,14 +23,150 @@ enum GameState {
 #[derive(Resource)]
 pub struct MainFont(Handle<Font>);
 
+/// Optional gameplay rules, toggled from the settings menu.
+#[derive(Resource, Default)]
+pub struct GameRules {
+    pub pad_multiplier_decay: bool,
+    pub rival: bool,
+    pub fleet: bool,
+    pub realism: bool,
+    pub practice: bool,
+    /// Initial velocity of practice runs, which start 150 m above their pad.
+    pub practice_velocity: Vec2,
+    pub win_tolerances: WinTolerances,
+    /// Tick while drifting sideways near the ground.
+    pub drift_tick: bool,
+    pub camera_framing: CameraFraming,
+    /// Show what the autoland controller would fly, as a training aid.
+    pub guidance: bool,
+    /// Sometimes start runs with the lander in trouble.
+    pub emergency_starts: bool,
+    pub physics_quality: PhysicsQuality,
+}
+
+/// How still a lander has to sit on a pad, and for how long, to count as landed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WinTolerances {
+    pub max_speed: f32,         // m/s
+    pub max_angular_speed: f32, // rad/s
+    pub max_tilt: f32,          // radians from upright
+    pub settle_time: f32,       // seconds
+}
+
+impl WinTolerances {
+    pub const RELAXED: WinTolerances = WinTolerances {
+        max_speed: 8.0,
+        max_angular_speed: 0.2,
+        max_tilt: std::f32::consts::FRAC_PI_2,
+        settle_time: 2.0,
+    };
+    pub const NORMAL: WinTolerances = WinTolerances {
+        max_speed: 5.0,
+        max_angular_speed: 0.1,
+        max_tilt: std::f32::consts::FRAC_PI_2,
+        settle_time: 3.0,
+    };
+    pub const STRICT: WinTolerances = WinTolerances {
+        max_speed: 3.0,
+        max_angular_speed: 0.05,
+        max_tilt: std::f32::consts::FRAC_PI_6,
+        settle_time: 4.0,
+    };
+}
+
+impl Default for WinTolerances {
+    fn default() -> Self {
+        WinTolerances::NORMAL
+    }
+}
+
+/// How the in-game camera keeps the lander in frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CameraFraming {
+    /// Fraction of the viewport the framing target moves in freely.
+    pub dead_zone: Vec2,
+    /// Fraction of the viewport height the lander sits above the center.
+    pub vertical_bias: f32,
+    /// Seconds of velocity the framing target leads the lander by.
+    pub look_ahead: f32,
+}
+
+impl CameraFraming {
+    /// Follows sideways once the lander leaves the middle half of the screen.
+    pub const CLASSIC: CameraFraming = CameraFraming {
+        dead_zone: Vec2::new(0.5, 1.0),
+        vertical_bias: 0.0,
+        look_ahead: 0.0,
+    };
+    pub const DESCENT: CameraFraming = CameraFraming {
+        dead_zone: Vec2::new(0.3, 0.3),
+        vertical_bias: 0.2,
+        look_ahead: 0.5,
+    };
+    pub const TIGHT: CameraFraming = CameraFraming {
+        dead_zone: Vec2::new(0.1, 0.1),
+        vertical_bias: 0.1,
+        look_ahead: 1.0,
+    };
+}
+
+impl Default for CameraFraming {
+    fn default() -> Self {
+        CameraFraming::CLASSIC
+    }
+}
+
+/// Physics solver effort, trading accuracy for speed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PhysicsQuality {
+    pub substeps: u32,
+    pub restitution_iterations: usize,
+}
+
+impl PhysicsQuality {
+    /// For the browser build and low-end machines.
+    pub const POTATO: PhysicsQuality = PhysicsQuality {
+        substeps: 2,
+        restitution_iterations: 1,
+    };
+    pub const STANDARD: PhysicsQuality = PhysicsQuality {
+        substeps: 6,
+        restitution_iterations: 1,
+    };
+    /// For modes whose results have to match between machines.
+    pub const PRECISION: PhysicsQuality = PhysicsQuality {
+        substeps: 12,
+        restitution_iterations: 2,
+    };
+}
+
+impl Default for PhysicsQuality {
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            PhysicsQuality::POTATO
+        } else {
+            PhysicsQuality::STANDARD
+        }
+    }
+}
+
 pub struct GameAppPlugin;
 
 impl Plugin for GameAppPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((DefaultPlugins, PhysicsPlugins::default()))
             .init_state::<GameState>()
+            .init_resource::<GameRules>()
             .add_systems(Startup, setup)
-            .add_plugins((main_menu::plugin, game::plugin));
+            .add_plugins((
+                capture::plugin,
+                crash_report::plugin,
+                main_menu::plugin,
+                game::plugin,
+                heatmap::plugin,
+                stats::plugin,
+                run_log::plugin,
+            ));
     }
 }
 
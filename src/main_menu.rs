@@ -1,4 +1,5 @@
 use super::*;
+use crate::{stats::SessionStats, typography::TextStyle};
 
 use bevy::{
     color::palettes::css::{BLACK, WHITE},
@@ -8,6 +9,8 @@ use bevy::{
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
 enum MenuState {
     Main,
+    Settings,
+    Recap,
     #[default]
     Disabled,
 }
@@ -15,6 +18,15 @@ enum MenuState {
 #[derive(Component)]
 struct OnMainMenuScreen;
 
+const PRACTICE_APPROACHES: [Vec2; 4] = [
+    Vec2::ZERO,
+    Vec2::new(5.0, -5.0),
+    Vec2::new(-5.0, -5.0),
+    Vec2::new(15.0, -10.0),
+];
+
+const SESSION_RECAP_RUNS: u32 = 5; // runs between recaps shown on returning to the menu
+
 const TEXT_COLOR: Color = Color::Srgba(WHITE);
 const HOVER_TEXT_COLOR: Color = Color::Srgba(BLACK);
 const NORMAL_BUTTON: Color = Color::Srgba(BLACK);
@@ -25,19 +37,186 @@ const PRESSED_BUTTON: Color = Color::Srgba(WHITE);
 #[derive(Component)]
 struct SelectedOption;
 
+/// The recap on screen was asked for by quitting, so closing it exits the game.
+#[derive(Resource)]
+struct QuitAfterRecap;
+
 #[derive(Component)]
 enum MenuButtonAction {
     Play,
+    Settings,
+    ToggleSetting(Setting),
+    BackToMainMenu,
     Quit,
+    Exit,
+}
+
+#[derive(Component, Clone, Copy)]
+enum Setting {
+    PadMultiplierDecay,
+    Rival,
+    Fleet,
+    Realism,
+    Practice,
+    PracticeApproach,
+    WinTolerances,
+    DriftTick,
+    CameraFraming,
+    Guidance,
+    EmergencyStarts,
+    PhysicsQuality,
+}
+
+impl Setting {
+    const ALL: [Setting; 12] = [
+        Setting::PadMultiplierDecay,
+        Setting::Rival,
+        Setting::Fleet,
+        Setting::Realism,
+        Setting::Practice,
+        Setting::PracticeApproach,
+        Setting::WinTolerances,
+        Setting::DriftTick,
+        Setting::CameraFraming,
+        Setting::Guidance,
+        Setting::EmergencyStarts,
+        Setting::PhysicsQuality,
+    ];
+
+    fn label(self, rules: &GameRules) -> String {
+        match self {
+            Setting::PadMultiplierDecay => {
+                format!("Pad Decay: {}", on_off(rules.pad_multiplier_decay))
+            }
+            Setting::Rival => format!("Rival Lander: {}", on_off(rules.rival)),
+            Setting::Fleet => format!("Fleet Mode: {}", on_off(rules.fleet)),
+            Setting::Realism => format!("Realism Mode: {}", on_off(rules.realism)),
+            Setting::Practice => format!("Practice Final: {}", on_off(rules.practice)),
+            Setting::PracticeApproach => format!(
+                "Practice Approach: {:.0}, {:.0} m/s",
+                rules.practice_velocity.x, rules.practice_velocity.y
+            ),
+            Setting::WinTolerances => {
+                let name = if rules.win_tolerances == WinTolerances::RELAXED {
+                    "RELAXED"
+                } else if rules.win_tolerances == WinTolerances::STRICT {
+                    "STRICT"
+                } else {
+                    "NORMAL"
+                };
+                format!("Landing Tolerance: {}", name)
+            }
+            Setting::DriftTick => format!("Drift Tick: {}", on_off(rules.drift_tick)),
+            Setting::CameraFraming => {
+                let name = if rules.camera_framing == CameraFraming::DESCENT {
+                    "DESCENT"
+                } else if rules.camera_framing == CameraFraming::TIGHT {
+                    "TIGHT"
+                } else {
+                    "CLASSIC"
+                };
+                format!("Camera: {}", name)
+            }
+            Setting::Guidance => format!("Guidance Cue: {}", on_off(rules.guidance)),
+            Setting::EmergencyStarts => {
+                format!("Emergency Starts: {}", on_off(rules.emergency_starts))
+            }
+            Setting::PhysicsQuality => {
+                let name = if rules.physics_quality == PhysicsQuality::POTATO {
+                    "POTATO"
+                } else if rules.physics_quality == PhysicsQuality::PRECISION {
+                    "PRECISION"
+                } else {
+                    "STANDARD"
+                };
+                format!("Physics: {}", name)
+            }
+        }
+    }
+
+    fn toggle(self, rules: &mut GameRules) {
+        match self {
+            Setting::PadMultiplierDecay => {
+                rules.pad_multiplier_decay = !rules.pad_multiplier_decay;
+            }
+            Setting::Rival => {
+                rules.rival = !rules.rival;
+            }
+            Setting::Fleet => {
+                rules.fleet = !rules.fleet;
+            }
+            Setting::Realism => {
+                rules.realism = !rules.realism;
+            }
+            Setting::Practice => {
+                rules.practice = !rules.practice;
+            }
+            Setting::PracticeApproach => {
+                let current = PRACTICE_APPROACHES
+                    .iter()
+                    .position(|&velocity| velocity == rules.practice_velocity)
+                    .unwrap_or(0);
+                rules.practice_velocity =
+                    PRACTICE_APPROACHES[(current + 1) % PRACTICE_APPROACHES.len()];
+            }
+            Setting::WinTolerances => {
+                rules.win_tolerances = if rules.win_tolerances == WinTolerances::RELAXED {
+                    WinTolerances::NORMAL
+                } else if rules.win_tolerances == WinTolerances::NORMAL {
+                    WinTolerances::STRICT
+                } else {
+                    WinTolerances::RELAXED
+                };
+            }
+            Setting::DriftTick => {
+                rules.drift_tick = !rules.drift_tick;
+            }
+            Setting::CameraFraming => {
+                rules.camera_framing = if rules.camera_framing == CameraFraming::CLASSIC {
+                    CameraFraming::DESCENT
+                } else if rules.camera_framing == CameraFraming::DESCENT {
+                    CameraFraming::TIGHT
+                } else {
+                    CameraFraming::CLASSIC
+                };
+            }
+            Setting::Guidance => {
+                rules.guidance = !rules.guidance;
+            }
+            Setting::EmergencyStarts => {
+                rules.emergency_starts = !rules.emergency_starts;
+            }
+            Setting::PhysicsQuality => {
+                rules.physics_quality = if rules.physics_quality == PhysicsQuality::POTATO {
+                    PhysicsQuality::STANDARD
+                } else if rules.physics_quality == PhysicsQuality::STANDARD {
+                    PhysicsQuality::PRECISION
+                } else {
+                    PhysicsQuality::POTATO
+                };
+            }
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "ON" } else { "OFF" }
 }
 
 pub(crate) fn plugin(app: &mut App) {
     app.init_state::<MenuState>()
         .add_systems(OnEnter(GameState::Menu), menu_setup)
         .add_systems(OnEnter(MenuState::Main), main_menu_setup)
+        .add_systems(OnEnter(MenuState::Settings), settings_menu_setup)
+        .add_systems(OnEnter(MenuState::Recap), recap_setup)
         .add_systems(
             Update,
-            (menu_action, button_system).run_if(in_state(GameState::Menu)),
+            (
+                menu_action,
+                button_system,
+                setting_text_system.run_if(resource_changed::<GameRules>),
+            )
+                .run_if(in_state(GameState::Menu)),
         )
         .add_systems(OnExit(MenuState::Main), cleanup_main_menu_screen)
         .add_systems(OnExit(GameState::Menu), cleanup_menu_screen);
@@ -103,8 +282,19 @@ fn button_system(
     }
 }
 
-fn menu_setup(mut menu_state: ResMut<NextState<MenuState>>) {
-    menu_state.set(MenuState::Main);
+fn menu_setup(
+    mut menu_state: ResMut<NextState<MenuState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    retry_seed: Option<Res<game::RetrySeed>>,
+    session_stats: Res<SessionStats>,
+) {
+    if retry_seed.is_some() {
+        game_state.set(GameState::Game);
+    } else if session_stats.runs >= session_stats.recapped_runs + SESSION_RECAP_RUNS {
+        menu_state.set(MenuState::Recap);
+    } else {
+        menu_state.set(MenuState::Main);
+    }
 }
 
 fn cleanup_menu_screen(mut _commands: Commands) {
@@ -120,9 +310,8 @@ fn main_menu_setup(
     mut clear_color: ResMut<ClearColor>,
     _asset_server: Res<AssetServer>,
     font_family: Res<MainFont>,
+    previous_crash: Option<Res<crash_report::PreviousCrash>>,
 ) {
-    let font_family = &font_family.0;
-
     let button_node = Node {
         width: px(300),
         height: px(65),
@@ -132,11 +321,7 @@ fn main_menu_setup(
         border: UiRect::all(px(2)),
         ..default()
     };
-    let button_text_font = TextFont {
-        font_size: 33.0,
-        font: font_family.clone(),
-        ..default()
-    };
+    let button_text_font = font_family.text_font(TextStyle::Button);
 
     clear_color.0 = BLACK.into();
 
@@ -159,11 +344,7 @@ fn main_menu_setup(
             children![
                 (
                     Text::new("Moon Landr"),
-                    TextFont {
-                        font_size: 67.0,
-                        font: font_family.clone(),
-                        ..default()
-                    },
+                    font_family.text_font(TextStyle::TitleLarge),
                     TextColor(TEXT_COLOR),
                     Node {
                         margin: UiRect::all(px(50)),
@@ -182,19 +363,18 @@ fn main_menu_setup(
                         TextColor(TEXT_COLOR),
                     ),]
                 ),
-                /*(
+                (
                     Button,
                     button_node.clone(),
                     BackgroundColor(NORMAL_BUTTON),
+                    BorderColor::all(TEXT_COLOR),
                     MenuButtonAction::Settings,
-                    children![
-                        (
-                            Text::new("Settings"),
-                            button_text_font.clone(),
-                            TextColor(TEXT_COLOR),
-                        ),
-                    ]
-                ),*/
+                    children![(
+                        Text::new("Settings"),
+                        button_text_font.clone(),
+                        TextColor(TEXT_COLOR),
+                    ),]
+                ),
                 (
                     Button,
                     button_node,
@@ -206,27 +386,229 @@ fn main_menu_setup(
             ]
         )],
     ));
+
+    if let Some(previous_crash) = previous_crash {
+        commands.spawn((
+            DespawnOnExit(MenuState::Main),
+            Text::new(format!(
+                "The game crashed last time. A report was saved to {}",
+                previous_crash.0.display()
+            )),
+            font_family.text_font(TextStyle::Notice),
+            TextColor(TEXT_COLOR),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: px(20),
+                left: px(20),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn settings_menu_setup(mut commands: Commands, rules: Res<GameRules>, font_family: Res<MainFont>) {
+    let button_node = Node {
+        width: px(420),
+        height: px(55),
+        margin: UiRect::all(px(10)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        border: UiRect::all(px(2)),
+        ..default()
+    };
+    let button_text_font = font_family.text_font(TextStyle::ButtonSmall);
+
+    commands
+        .spawn((
+            DespawnOnExit(MenuState::Settings),
+            Node {
+                width: percent(100),
+                height: percent(100),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Settings"),
+                        font_family.text_font(TextStyle::Title),
+                        TextColor(TEXT_COLOR),
+                        Node {
+                            margin: UiRect::all(px(30)),
+                            ..default()
+                        },
+                    ));
+
+                    for setting in Setting::ALL {
+                        parent.spawn((
+                            Button,
+                            button_node.clone(),
+                            BackgroundColor(NORMAL_BUTTON),
+                            BorderColor::all(TEXT_COLOR),
+                            MenuButtonAction::ToggleSetting(setting),
+                            children![(
+                                setting,
+                                Text::new(setting.label(&rules)),
+                                button_text_font.clone(),
+                                TextColor(TEXT_COLOR),
+                            )],
+                        ));
+                    }
+
+                    parent.spawn((
+                        Button,
+                        button_node.clone(),
+                        BackgroundColor(NORMAL_BUTTON),
+                        BorderColor::all(TEXT_COLOR),
+                        MenuButtonAction::BackToMainMenu,
+                        children![(
+                            Text::new("Back"),
+                            button_text_font.clone(),
+                            TextColor(TEXT_COLOR),
+                        )],
+                    ));
+                });
+        });
+}
+
+/// Sums up the runs flown since the game started.
+fn recap_setup(
+    mut commands: Commands,
+    mut session_stats: ResMut<SessionStats>,
+    quit_after_recap: Option<Res<QuitAfterRecap>>,
+    font_family: Res<MainFont>,
+) {
+    session_stats.recapped_runs = session_stats.runs;
+
+    let mut lines = vec![
+        format!("Runs flown: {}", session_stats.runs),
+        format!(
+            "Landings: {}  Crashes: {}",
+            session_stats.landings, session_stats.crashes
+        ),
+        match session_stats.best_score {
+            Some(score) => format!("Best score: {:.2}", score),
+            None => "Best score: no landings yet".to_string(),
+        },
+        format!("Distance flown: {:.0} m", session_stats.distance),
+    ];
+    if !session_stats.highlights.is_empty() {
+        lines.push(String::new());
+        lines.extend(session_stats.highlights.iter().cloned());
+    }
+
+    let (button_label, button_action) = if quit_after_recap.is_some() {
+        ("Quit", MenuButtonAction::Exit)
+    } else {
+        ("Continue", MenuButtonAction::BackToMainMenu)
+    };
+
+    commands.spawn((
+        DespawnOnExit(MenuState::Recap),
+        Node {
+            width: percent(100),
+            height: percent(100),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        children![(
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            children![
+                (
+                    Text::new("Session Recap"),
+                    font_family.text_font(TextStyle::Title),
+                    TextColor(TEXT_COLOR),
+                    Node {
+                        margin: UiRect::all(px(30)),
+                        ..default()
+                    },
+                ),
+                (
+                    Text::new(lines.join("\n")),
+                    font_family.text_font(TextStyle::Body),
+                    TextColor(TEXT_COLOR),
+                    TextLayout::new_with_justify(Justify::Center),
+                ),
+                (
+                    Button,
+                    Node {
+                        width: px(300),
+                        height: px(65),
+                        margin: UiRect::all(px(30)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        border: UiRect::all(px(2)),
+                        ..default()
+                    },
+                    BackgroundColor(NORMAL_BUTTON),
+                    BorderColor::all(TEXT_COLOR),
+                    button_action,
+                    children![(
+                        Text::new(button_label),
+                        font_family.text_font(TextStyle::Button),
+                        TextColor(TEXT_COLOR),
+                    )],
+                ),
+            ]
+        )],
+    ));
+}
+
+fn setting_text_system(rules: Res<GameRules>, mut texts: Query<(&Setting, &mut Text)>) {
+    for (setting, mut text) in &mut texts {
+        text.0 = setting.label(&rules);
+    }
 }
 
 fn menu_action(
+    mut commands: Commands,
     interaction_query: Query<
         (&Interaction, &MenuButtonAction),
         (Changed<Interaction>, With<Button>),
     >,
+    session_stats: Res<SessionStats>,
     mut app_exit_writer: MessageWriter<AppExit>,
     mut menu_state: ResMut<NextState<MenuState>>,
     mut game_state: ResMut<NextState<GameState>>,
+    mut rules: ResMut<GameRules>,
 ) {
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button_action {
-                MenuButtonAction::Quit => {
+                // Sessions with runs in them close on a recap
+                MenuButtonAction::Quit if session_stats.runs > 0 => {
+                    commands.insert_resource(QuitAfterRecap);
+                    menu_state.set(MenuState::Recap);
+                }
+                MenuButtonAction::Quit | MenuButtonAction::Exit => {
                     app_exit_writer.write(AppExit::Success);
                 }
                 MenuButtonAction::Play => {
                     game_state.set(GameState::Game);
                     menu_state.set(MenuState::Disabled);
                 }
+                MenuButtonAction::Settings => {
+                    menu_state.set(MenuState::Settings);
+                }
+                MenuButtonAction::ToggleSetting(setting) => {
+                    setting.toggle(&mut rules);
+                }
+                MenuButtonAction::BackToMainMenu => {
+                    menu_state.set(MenuState::Main);
+                }
             }
         }
     }
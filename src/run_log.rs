@@ -0,0 +1,124 @@
+//! JSON lines log of run events, one object per line, for external tools to
+//! follow the game as it is played.
+
+use crate::stats::{RunFinished, RunOutcome};
+
+use bevy::prelude::*;
+
+use std::{
+    fs::File,
+    io::{self, LineWriter, Write},
+};
+
+const RUN_LOG_FLAG: &str = "--run-log";
+
+/// Gameplay moments worth reporting to external tooling.
+#[derive(Message, Clone, Copy, Debug)]
+pub(crate) enum RunEvent {
+    Started { seed: u32 },
+    PhaseChanged { phase: &'static str },
+    PadEntered { multiplier: f32 },
+    Touchdown { impulse: f32 },
+}
+
+/// Destination of the JSON lines event log, enabled with `--run-log <path>`
+/// (`-` for stdout).
+#[derive(Resource)]
+struct RunLog(Box<dyn Write + Send + Sync>);
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_message::<RunEvent>();
+
+    let Some(target) = run_log_target() else {
+        return;
+    };
+
+    let writer: Box<dyn Write + Send + Sync> = if target == "-" {
+        Box::new(LineWriter::new(io::stdout()))
+    } else {
+        match File::create(&target) {
+            Ok(file) => Box::new(LineWriter::new(file)),
+            Err(error) => {
+                warn!("Could not open run log {}: {}", target, error);
+                return;
+            }
+        }
+    };
+
+    app.insert_resource(RunLog(writer))
+        .add_systems(Update, run_log_system);
+}
+
+fn run_log_target() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == RUN_LOG_FLAG {
+            return args.next();
+        }
+        if let Some(target) = arg.strip_prefix("--run-log=") {
+            return Some(target.to_string());
+        }
+    }
+    None
+}
+
+fn run_log_system(
+    time: Res<Time<Real>>,
+    mut run_events: MessageReader<RunEvent>,
+    mut runs_finished: MessageReader<RunFinished>,
+    mut run_log: ResMut<RunLog>,
+) {
+    let t = time.elapsed_secs();
+
+    let mut lines: Vec<String> = run_events
+        .read()
+        .map(|event| match event {
+            RunEvent::Started { seed } => {
+                format!(
+                    "{{\"event\":\"run_started\",\"t\":{:.3},\"seed\":{}}}",
+                    t, seed
+                )
+            }
+            RunEvent::PhaseChanged { phase } => format!(
+                "{{\"event\":\"phase_changed\",\"t\":{:.3},\"phase\":\"{}\"}}",
+                t, phase
+            ),
+            RunEvent::PadEntered { multiplier } => format!(
+                "{{\"event\":\"pad_entered\",\"t\":{:.3},\"multiplier\":{:.2}}}",
+                t, multiplier
+            ),
+            RunEvent::Touchdown { impulse } => format!(
+                "{{\"event\":\"touchdown\",\"t\":{:.3},\"impulse\":{:.1}}}",
+                t, impulse
+            ),
+        })
+        .collect();
+
+    lines.extend(runs_finished.read().map(|run| {
+        let outcome = match run.outcome {
+            RunOutcome::Landed => "landed",
+            RunOutcome::Crashed => "crashed",
+            RunOutcome::Aborted => "aborted",
+            RunOutcome::Quit => "quit",
+        };
+        let score = run
+            .score
+            .map_or("null".to_string(), |score| format!("{:.2}", score));
+        format!(
+            "{{\"event\":\"run_finished\",\"t\":{:.3},\"outcome\":\"{}\",\"duration\":{:.3},\"distance\":{:.1},\"score\":{},\"practice\":{}}}",
+            t,
+            outcome,
+            run.duration.as_secs_f32(),
+            run.distance,
+            score,
+            run.practice
+        )
+    }));
+
+    for line in lines {
+        if let Err(error) = writeln!(run_log.0, "{}", line) {
+            warn!("Could not write run log: {}", error);
+            return;
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+
+use std::{fs, path::PathBuf, time::Duration};
+
+const STATS_FILE_NAME: &str = "stats.txt";
+pub(crate) const GIMBAL_UNLOCK_LANDINGS: u32 = 10; // lifetime landings needed to unlock the engine gimbal
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum RunOutcome {
+    Landed,
+    Crashed,
+    Aborted,
+    /// Left to the menu mid-flight.
+    Quit,
+}
+
+/// Sent once when a run ends, however it ends.
+#[derive(Message, Clone, Copy, Debug)]
+pub(crate) struct RunFinished {
+    pub(crate) outcome: RunOutcome,
+    pub(crate) duration: Duration,
+    pub(crate) distance: f32,
+    pub(crate) score: Option<f32>,
+    /// Terrain seed the run was flown on.
+    pub(crate) seed: u32,
+    /// Where the lander was when the run ended.
+    pub(crate) position: Vec2,
+    /// Practice runs are logged but kept out of the statistics and unlocks.
+    pub(crate) practice: bool,
+}
+
+/// Totals across every run ever flown, persisted in the data directory.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct LifetimeStats {
+    pub(crate) runs: u32,
+    pub(crate) landings: u32,
+    pub(crate) crashes: u32,
+    pub(crate) aborts: u32,
+    pub(crate) quits: u32,
+    pub(crate) flight_time: f32,
+    pub(crate) distance: f32,
+    pub(crate) best_score: f32,
+}
+
+impl LifetimeStats {
+    pub(crate) fn gimbal_unlocked(&self) -> bool {
+        self.landings >= GIMBAL_UNLOCK_LANDINGS
+    }
+
+    fn record(&mut self, run: &RunFinished) {
+        self.runs += 1;
+        match run.outcome {
+            RunOutcome::Landed => self.landings += 1,
+            RunOutcome::Crashed => self.crashes += 1,
+            RunOutcome::Aborted => self.aborts += 1,
+            RunOutcome::Quit => self.quits += 1,
+        }
+        self.flight_time += run.duration.as_secs_f32();
+        self.distance += run.distance;
+        if let Some(score) = run.score {
+            self.best_score = self.best_score.max(score);
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, f32); 8] {
+        [
+            ("runs", self.runs as f32),
+            ("landings", self.landings as f32),
+            ("crashes", self.crashes as f32),
+            ("aborts", self.aborts as f32),
+            ("quits", self.quits as f32),
+            ("flight_time", self.flight_time),
+            ("distance", self.distance),
+            ("best_score", self.best_score),
+        ]
+    }
+
+    fn load() -> Self {
+        let mut stats = LifetimeStats::default();
+
+        let Ok(contents) = fs::read_to_string(data_dir().join(STATS_FILE_NAME)) else {
+            return stats;
+        };
+
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<f32>() else {
+                continue;
+            };
+            match key.trim() {
+                "runs" => stats.runs = value as u32,
+                "landings" => stats.landings = value as u32,
+                "crashes" => stats.crashes = value as u32,
+                "aborts" => stats.aborts = value as u32,
+                "quits" => stats.quits = value as u32,
+                "flight_time" => stats.flight_time = value,
+                "distance" => stats.distance = value,
+                "best_score" => stats.best_score = value,
+                _ => {}
+            }
+        }
+
+        stats
+    }
+
+    fn save(&self) {
+        let contents: String = self
+            .fields()
+            .iter()
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect();
+
+        if let Err(error) = fs::create_dir_all(data_dir())
+            .and_then(|_| fs::write(data_dir().join(STATS_FILE_NAME), contents))
+        {
+            warn!("Could not save statistics: {}", error);
+        }
+    }
+
+    pub(crate) fn to_csv(&self) -> String {
+        let mut csv = String::from("stat,value\n");
+        for (key, value) in self.fields() {
+            csv.push_str(&format!("{},{}\n", key, value));
+        }
+        csv
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields()
+            .iter()
+            .map(|(key, value)| format!("  \"{}\": {}", key, value))
+            .collect();
+        format!("{{\n{}\n}}\n", fields.join(",\n"))
+    }
+}
+
+/// Totals for the runs flown since the game started, for the session recap.
+#[derive(Resource, Default, Debug)]
+pub(crate) struct SessionStats {
+    pub(crate) runs: u32,
+    pub(crate) landings: u32,
+    pub(crate) crashes: u32,
+    pub(crate) distance: f32,
+    pub(crate) best_score: Option<f32>,
+    /// Notable moments of the session, in the order they happened.
+    pub(crate) highlights: Vec<String>,
+    /// Runs flown when the recap was last shown.
+    pub(crate) recapped_runs: u32,
+}
+
+impl SessionStats {
+    /// Adds `run`, before it is added to `lifetime_stats`.
+    fn record(&mut self, run: &RunFinished, lifetime_stats: &LifetimeStats) {
+        self.runs += 1;
+        match run.outcome {
+            RunOutcome::Landed => self.landings += 1,
+            RunOutcome::Crashed => self.crashes += 1,
+            RunOutcome::Aborted | RunOutcome::Quit => {}
+        }
+        self.distance += run.distance;
+
+        if let Some(score) = run.score {
+            self.best_score = Some(self.best_score.map_or(score, |best| best.max(score)));
+
+            if lifetime_stats.runs > 0 && score > lifetime_stats.best_score {
+                self.highlights
+                    .push(format!("New personal best: {:.2}", score));
+            }
+        }
+
+        if run.outcome == RunOutcome::Landed {
+            if lifetime_stats.landings == 0 {
+                self.highlights.push("First landing ever".to_string());
+            }
+            if lifetime_stats.landings + 1 == GIMBAL_UNLOCK_LANDINGS {
+                self.highlights.push("Engine gimbal unlocked".to_string());
+            }
+        }
+    }
+}
+
+/// Directory for saves and exports, relative to the working directory.
+pub(crate) fn data_dir() -> PathBuf {
+    PathBuf::from("data")
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.add_message::<RunFinished>()
+        .insert_resource(LifetimeStats::load())
+        .init_resource::<SessionStats>()
+        .add_systems(Update, record_run_system);
+}
+
+fn record_run_system(
+    mut runs_finished: MessageReader<RunFinished>,
+    mut lifetime_stats: ResMut<LifetimeStats>,
+    mut session_stats: ResMut<SessionStats>,
+) {
+    let mut changed = false;
+    for run in runs_finished.read().filter(|run| !run.practice) {
+        session_stats.record(run, &lifetime_stats);
+        lifetime_stats.record(run);
+        changed = true;
+    }
+
+    if changed {
+        lifetime_stats.save();
+    }
+}
@@ -0,0 +1,161 @@
+//! Sounds generated in code instead of shipped as assets, as plain sample
+//! buffers plus a WAV encoder to hand them to the audio engine.
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::f32::consts::{PI, TAU};
+
+pub(crate) const SAMPLE_RATE: u32 = 22050;
+const LOOP_CROSSFADE: f32 = 0.25; // seconds blended across the loop seam
+
+const MUSIC_BEAT: f32 = 0.5; // seconds, 120 bpm
+const MUSIC_CHORD_BEATS: usize = 4;
+/// Chord tones, in semitones above A, of the progression all music stems follow.
+const MUSIC_PROGRESSION: [[f32; 3]; 4] = [
+    [0.0, 3.0, 7.0],  // A minor
+    [-4.0, 0.0, 3.0], // F major
+    [3.0, 7.0, 10.0], // C major
+    [-2.0, 2.0, 5.0], // G major
+];
+/// Length of every music stem, so stems started together stay in step.
+pub(crate) const MUSIC_LOOP_LENGTH: f32 =
+    MUSIC_BEAT * (MUSIC_CHORD_BEATS * MUSIC_PROGRESSION.len()) as f32;
+
+/// 16-bit mono PCM WAV file holding `samples`, clamped to [-1, 1].
+pub(crate) fn wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+
+    for sample in samples {
+        let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wav
+}
+
+/// Buffer of `seconds` of audio, with `sample(t)` evaluated at each sample time.
+fn render(seconds: f32, mut sample: impl FnMut(f32) -> f32) -> Vec<f32> {
+    let len = (seconds * SAMPLE_RATE as f32) as usize;
+    (0..len)
+        .map(|i| sample(i as f32 / SAMPLE_RATE as f32))
+        .collect()
+}
+
+/// Renders `seconds` plus a crossfade tail, and blends the tail into the start
+/// so the buffer loops without a click.
+fn render_loop(seconds: f32, sample: impl FnMut(f32) -> f32) -> Vec<f32> {
+    let mut samples = render(seconds + LOOP_CROSSFADE, sample);
+    let fade_len = (LOOP_CROSSFADE * SAMPLE_RATE as f32) as usize;
+    let len = samples.len() - fade_len;
+
+    let (head, tail) = samples.split_at_mut(len);
+    for (i, (sample, tail_sample)) in head.iter_mut().zip(tail.iter()).enumerate() {
+        let blend = i as f32 / fade_len as f32;
+        *sample = *sample * blend + tail_sample * (1.0 - blend);
+    }
+    samples.truncate(len);
+
+    samples
+}
+
+/// Low rumbling noise with slow gusts, like wind over dust.
+pub(crate) fn wind(seconds: f32, seed: u64) -> Vec<f32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut level = 0.0;
+
+    render_loop(seconds, |t| {
+        // Leaky integration of white noise leaves mostly low frequencies
+        level = level * 0.995 + rng.random_range(-1.0..1.0) * 0.05;
+        let gusts = 0.6 + 0.4 * (TAU * t * 2.0 / seconds).sin();
+        level * gusts
+    })
+}
+
+/// Frequency `semitones` above `base`.
+fn note(base: f32, semitones: f32) -> f32 {
+    base * 2.0f32.powf(semitones / 12.0)
+}
+
+/// Chord playing at `t`, and how far into it `t` is.
+fn chord_at(t: f32) -> (&'static [f32; 3], f32) {
+    let chord_length = MUSIC_BEAT * MUSIC_CHORD_BEATS as f32;
+    let t = t.rem_euclid(MUSIC_LOOP_LENGTH);
+    let index = ((t / chord_length) as usize).min(MUSIC_PROGRESSION.len() - 1);
+    (&MUSIC_PROGRESSION[index], t - index as f32 * chord_length)
+}
+
+/// Sustained chords swelling in and out, the calm bed of the music.
+pub(crate) fn music_pad() -> Vec<f32> {
+    let chord_length = MUSIC_BEAT * MUSIC_CHORD_BEATS as f32;
+
+    render_loop(MUSIC_LOOP_LENGTH, |t| {
+        let (chord, into_chord) = chord_at(t);
+        // Swelling in and out of each chord keeps the changes from clicking
+        let envelope = (PI * into_chord / chord_length).sin().sqrt();
+        let voices: f32 = chord
+            .iter()
+            .map(|&semitones| (TAU * note(220.0, semitones) * t).sin())
+            .sum();
+        voices / 3.0 * envelope * 0.6
+    })
+}
+
+/// Low pulsing eighth notes under a trembling high fifth.
+pub(crate) fn music_tension() -> Vec<f32> {
+    let pulse_length = MUSIC_BEAT / 2.0;
+
+    render_loop(MUSIC_LOOP_LENGTH, |t| {
+        let (chord, _) = chord_at(t);
+        let since_pulse = t.rem_euclid(pulse_length);
+
+        let root = note(110.0, chord[0]);
+        let pulse = ((TAU * root * t).sin() + 0.5 * (TAU * 2.0 * root * t).sin())
+            * (-since_pulse * 12.0).exp();
+
+        let tremolo = 0.5 + 0.5 * (TAU * 8.0 * t).sin();
+        let fifth = (TAU * note(880.0, chord[2]) * t).sin() * tremolo;
+
+        pulse * 0.5 + fifth * 0.15
+    })
+}
+
+/// Bright sixteenth note arpeggios over the chords.
+pub(crate) fn music_triumph() -> Vec<f32> {
+    let step_length = MUSIC_BEAT / 4.0;
+
+    render_loop(MUSIC_LOOP_LENGTH, |t| {
+        let (chord, _) = chord_at(t);
+        let step = (t / step_length) as usize;
+        let since_step = t - step as f32 * step_length;
+
+        let semitones = chord[step % 3] + if step / 3 % 2 == 0 { 0.0 } else { 12.0 };
+        let frequency = note(440.0, semitones);
+        let bell = (TAU * frequency * t).sin() + 0.3 * (TAU * 2.0 * frequency * t).sin();
+
+        bell * (-since_step * 8.0).exp() * 0.4
+    })
+}
+
+/// Mains hum with a slow pulse, like machinery running inside a base.
+pub(crate) fn machinery(seconds: f32) -> Vec<f32> {
+    render_loop(seconds, |t| {
+        let hum = (TAU * 50.0 * t).sin() * 0.5
+            + (TAU * 100.0 * t).sin() * 0.3
+            + (TAU * 150.0 * t).sin() * 0.2;
+        let pulse = 0.7 + 0.3 * (TAU * 2.0 * t).sin();
+        hum * pulse * 0.5
+    })
+}
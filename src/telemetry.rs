@@ -0,0 +1,127 @@
+use crate::stats::{LifetimeStats, data_dir};
+
+use bevy::prelude::*;
+
+use std::{fs, io, path::PathBuf};
+
+/// Lander state at one instant of a run.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TelemetrySample {
+    pub(crate) time: f32,
+    pub(crate) position: Vec2,
+    pub(crate) velocity: Vec2,
+    pub(crate) angle: f32,
+    pub(crate) fuel: u32,
+    pub(crate) throttle: f32,
+}
+
+/// Samples of the current run, recorded at a fixed interval.
+#[derive(Resource)]
+pub(crate) struct FlightRecorder {
+    pub(crate) samples: Vec<TelemetrySample>,
+    pub(crate) timer: Timer,
+    pub(crate) distance: f32,
+}
+
+impl FlightRecorder {
+    pub(crate) fn new(interval: f32) -> Self {
+        FlightRecorder {
+            samples: Vec::new(),
+            timer: Timer::from_seconds(interval, TimerMode::Repeating),
+            distance: 0.0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, sample: TelemetrySample) {
+        if let Some(last) = self.samples.last() {
+            self.distance += last.position.distance(sample.position);
+        }
+        self.samples.push(sample);
+    }
+
+    /// Fuel burned or leaked so far, from the drops between samples and down to
+    /// `current_fuel`. Refuels don't count against it.
+    pub(crate) fn fuel_used(&self, current_fuel: u32) -> u32 {
+        let fuel = self
+            .samples
+            .iter()
+            .map(|sample| sample.fuel)
+            .chain([current_fuel]);
+        fuel.clone()
+            .zip(fuel.skip(1))
+            .map(|(before, after)| before.saturating_sub(after))
+            .sum()
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("time,x,y,vx,vy,angle,fuel,throttle\n");
+        for sample in &self.samples {
+            csv.push_str(&format!(
+                "{:.3},{:.3},{:.3},{:.3},{:.3},{:.4},{},{:.2}\n",
+                sample.time,
+                sample.position.x,
+                sample.position.y,
+                sample.velocity.x,
+                sample.velocity.y,
+                sample.angle,
+                sample.fuel,
+                sample.throttle
+            ));
+        }
+        csv
+    }
+
+    fn to_json(&self, seed: u32) -> String {
+        let samples: Vec<String> = self
+            .samples
+            .iter()
+            .map(|sample| {
+                format!(
+                    "    {{\"time\": {:.3}, \"x\": {:.3}, \"y\": {:.3}, \"vx\": {:.3}, \"vy\": {:.3}, \"angle\": {:.4}, \"fuel\": {}, \"throttle\": {:.2}}}",
+                    sample.time,
+                    sample.position.x,
+                    sample.position.y,
+                    sample.velocity.x,
+                    sample.velocity.y,
+                    sample.angle,
+                    sample.fuel,
+                    sample.throttle
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\n  \"seed\": {},\n  \"distance\": {:.3},\n  \"samples\": [\n{}\n  ]\n}}\n",
+            seed,
+            self.distance,
+            samples.join(",\n")
+        )
+    }
+}
+
+fn export_dir() -> PathBuf {
+    data_dir().join("exports")
+}
+
+/// Writes the run telemetry and lifetime statistics as CSV and JSON, returning
+/// the directory they were written to.
+pub(crate) fn export(
+    recorder: &FlightRecorder,
+    seed: u32,
+    lifetime_stats: &LifetimeStats,
+) -> io::Result<PathBuf> {
+    let dir = export_dir();
+    fs::create_dir_all(&dir)?;
+
+    let run_name = format!("run-{}-{}", seed, recorder.samples.len());
+    fs::write(dir.join(format!("{}.csv", run_name)), recorder.to_csv())?;
+    fs::write(
+        dir.join(format!("{}.json", run_name)),
+        recorder.to_json(seed),
+    )?;
+
+    fs::write(dir.join("lifetime-stats.csv"), lifetime_stats.to_csv())?;
+    fs::write(dir.join("lifetime-stats.json"), lifetime_stats.to_json())?;
+
+    Ok(dir)
+}
@@ -0,0 +1,564 @@
+//! Terrain generation as plain data: surface heights and pad sites for a chunk,
+//! computed from the run seed and the chunk origin without touching the ECS.
+
+use bevy::math::Vec2;
+use noiz::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+pub(crate) const CHUNK_WIDTH: f32 = 400.0;
+pub(crate) const CHUNK_GRANULARITY: u32 = 2; // units per sample point
+const CHUNK_NOISE_LAYERS: u32 = 12;
+const CHUNK_NOISE_PERSISTENCE: f32 = 0.7;
+const CHUNK_NOISE_LACUNARITY: f32 = 2.0;
+const CHUNK_HEIGHT_AMPLITUDE: f32 = 400.0;
+const CHUNK_BASE_HEIGHT: f32 = 400.0;
+
+const LAND_PAD_CHANCE: f64 = 0.7;
+const LAND_PAD_WIDTHS: [u32; 3] = [16, 24, 32]; // in world units, for small, medium, large pads
+const LAND_PAD_MULTIPLIERS: [f32; 3] = [5.0, 3.0, 2.0];
+const LAND_PAD_FLATNESS_TOLERANCE: f32 = 4.0; // max height difference between the pad ends
+const LAND_PAD_EDGE_MARGIN: u32 = 8; // min distance between a pad and the chunk edges, in world units
+const BASE_PAD_CHANCE: f64 = 0.25;
+const HAZARD_PAD_CHANCE: f64 = 0.2; // of pads other than bases
+const HAZARD_PAD_MULTIPLIER_FACTOR: f32 = 1.5;
+const SLOPED_PAD_SLOPE: f32 = 0.1; // rise over run, well within the landing tilt tolerance
+const BOULDER_PAD_FRACTION: f32 = 0.25; // of the pad width a boulder covers
+const BOULDER_PAD_MIN_WIDTH: u32 = 24; // narrower pads would leave no room for the lander
+
+const ICE_CHANCE: f64 = 0.1;
+const SOFT_REGOLITH_CHANCE: f64 = 0.15;
+const REGOLITH_MATERIAL: SurfaceMaterial = SurfaceMaterial::new(0.15, 0.5);
+const ICE_MATERIAL: SurfaceMaterial = SurfaceMaterial::new(0.1, 0.02).dominant();
+const SOFT_REGOLITH_MATERIAL: SurfaceMaterial = SurfaceMaterial::new(0.0, 0.9).dominant();
+const PAD_MATERIAL: SurfaceMaterial = SurfaceMaterial::new(0.05, 0.8);
+const LANDER_LEG_MATERIAL: SurfaceMaterial = SurfaceMaterial::new(0.2, 0.5);
+
+/// How bouncy and grippy a surface is.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SurfaceMaterial {
+    pub(crate) restitution: f32,
+    pub(crate) friction: f32,
+    /// On contact, the lower coefficients of both surfaces apply instead of
+    /// their average, so ice stays slippery and soft ground stays dead whatever
+    /// lands on it.
+    pub(crate) dominant: bool,
+}
+
+impl SurfaceMaterial {
+    pub(crate) const fn new(restitution: f32, friction: f32) -> Self {
+        SurfaceMaterial {
+            restitution,
+            friction,
+            dominant: false,
+        }
+    }
+
+    const fn dominant(self) -> Self {
+        SurfaceMaterial {
+            dominant: true,
+            ..self
+        }
+    }
+}
+
+/// What the ground of a chunk is made of.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Surface {
+    Regolith,
+    /// Icy patch with next to no friction.
+    Ice,
+    /// Loose regolith that soaks up bounces.
+    SoftRegolith,
+}
+
+/// Knobs of the terrain generator.
+#[derive(Clone, Debug)]
+pub(crate) struct TerrainParams {
+    pub(crate) chunk_width: f32,
+    pub(crate) granularity: u32,
+    pub(crate) noise_layers: u32,
+    pub(crate) noise_persistence: f32,
+    pub(crate) noise_lacunarity: f32,
+    pub(crate) height_amplitude: f32,
+    pub(crate) base_height: f32,
+    pub(crate) pad_chance: f64,
+    pub(crate) pad_widths: [u32; 3],
+    pub(crate) pad_multipliers: [f32; 3],
+    pub(crate) pad_flatness_tolerance: f32,
+    pub(crate) pad_edge_margin: u32,
+    pub(crate) base_pad_chance: f64,
+    pub(crate) hazard_pad_chance: f64,
+    pub(crate) hazard_pad_multiplier_factor: f32,
+    pub(crate) sloped_pad_slope: f32,
+    pub(crate) boulder_pad_fraction: f32,
+    pub(crate) boulder_pad_min_width: u32,
+    pub(crate) ice_chance: f64,
+    pub(crate) soft_regolith_chance: f64,
+    pub(crate) regolith_material: SurfaceMaterial,
+    pub(crate) ice_material: SurfaceMaterial,
+    pub(crate) soft_regolith_material: SurfaceMaterial,
+    pub(crate) pad_material: SurfaceMaterial,
+    pub(crate) lander_leg_material: SurfaceMaterial,
+}
+
+impl TerrainParams {
+    pub(crate) fn surface_material(&self, surface: Surface) -> SurfaceMaterial {
+        match surface {
+            Surface::Regolith => self.regolith_material,
+            Surface::Ice => self.ice_material,
+            Surface::SoftRegolith => self.soft_regolith_material,
+        }
+    }
+}
+
+impl Default for TerrainParams {
+    fn default() -> Self {
+        TerrainParams {
+            chunk_width: CHUNK_WIDTH,
+            granularity: CHUNK_GRANULARITY,
+            noise_layers: CHUNK_NOISE_LAYERS,
+            noise_persistence: CHUNK_NOISE_PERSISTENCE,
+            noise_lacunarity: CHUNK_NOISE_LACUNARITY,
+            height_amplitude: CHUNK_HEIGHT_AMPLITUDE,
+            base_height: CHUNK_BASE_HEIGHT,
+            pad_chance: LAND_PAD_CHANCE,
+            pad_widths: LAND_PAD_WIDTHS,
+            pad_multipliers: LAND_PAD_MULTIPLIERS,
+            pad_flatness_tolerance: LAND_PAD_FLATNESS_TOLERANCE,
+            pad_edge_margin: LAND_PAD_EDGE_MARGIN,
+            base_pad_chance: BASE_PAD_CHANCE,
+            hazard_pad_chance: HAZARD_PAD_CHANCE,
+            hazard_pad_multiplier_factor: HAZARD_PAD_MULTIPLIER_FACTOR,
+            sloped_pad_slope: SLOPED_PAD_SLOPE,
+            boulder_pad_fraction: BOULDER_PAD_FRACTION,
+            boulder_pad_min_width: BOULDER_PAD_MIN_WIDTH,
+            ice_chance: ICE_CHANCE,
+            soft_regolith_chance: SOFT_REGOLITH_CHANCE,
+            regolith_material: REGOLITH_MATERIAL,
+            ice_material: ICE_MATERIAL,
+            soft_regolith_material: SOFT_REGOLITH_MATERIAL,
+            pad_material: PAD_MATERIAL,
+            lander_leg_material: LANDER_LEG_MATERIAL,
+        }
+    }
+}
+
+/// What makes a pad harder to land on, for a higher multiplier.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum PadHazard {
+    /// Surface rising by this much per unit to the right.
+    Sloped(f32),
+    /// Boulder covering `fraction` of the pad width at its right end, or at its
+    /// left end when `right` is false. Touching it scrubs the landing.
+    Boulder { right: bool, fraction: f32 },
+}
+
+impl PadHazard {
+    /// Horizontal offset from the pad center to the middle of the stretch that
+    /// can be landed on.
+    pub(crate) fn clear_offset(self, pad_width: f32) -> f32 {
+        match self {
+            PadHazard::Sloped(_) => 0.0,
+            PadHazard::Boulder { right, fraction } => {
+                let offset = fraction * pad_width / 2.0;
+                if right { -offset } else { offset }
+            }
+        }
+    }
+
+    /// Width of the stretch that can be landed on.
+    pub(crate) fn clear_width(self, pad_width: f32) -> f32 {
+        match self {
+            PadHazard::Sloped(_) => pad_width,
+            PadHazard::Boulder { fraction, .. } => pad_width * (1.0 - fraction),
+        }
+    }
+}
+
+/// Flattened landing site inside a chunk.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PadSite {
+    /// Center of the pad surface, relative to the chunk.
+    pub(crate) center: Vec2,
+    pub(crate) width: u32,
+    pub(crate) multiplier: f32,
+    pub(crate) base: bool,
+    /// Indices of the first and last flattened heights.
+    pub(crate) samples: (usize, usize),
+    pub(crate) hazard: Option<PadHazard>,
+}
+
+/// Everything needed to build one chunk.
+#[derive(Clone, Debug)]
+pub(crate) struct ChunkData {
+    /// Surface heights, one every `granularity` units from the chunk start.
+    pub(crate) heights: Vec<f32>,
+    pub(crate) pad: Option<PadSite>,
+    /// Ground outside the pad, which always has the pad material.
+    pub(crate) surface: Surface,
+}
+
+impl ChunkData {
+    /// Surface points relative to the chunk.
+    pub(crate) fn points(&self, granularity: u32) -> Vec<Vec2> {
+        self.heights
+            .iter()
+            .enumerate()
+            .map(|(x, &height)| Vec2::new((x * granularity as usize) as f32, height))
+            .collect()
+    }
+}
+
+type TerrainNoise = Noise<
+    LayeredNoise<
+        Normed<f32>,
+        Persistence,
+        FractalLayers<Octave<MixCellGradients<OrthoGrid, Smoothstep, QuickGradients>>>,
+    >,
+>;
+
+pub(crate) struct TerrainGenerator {
+    noise: TerrainNoise,
+    params: TerrainParams,
+    seed: u32,
+}
+
+impl TerrainGenerator {
+    pub(crate) fn new(seed: u32, params: TerrainParams) -> Self {
+        let mut noise: TerrainNoise = Noise::from(LayeredNoise::new(
+            Normed::<f32>::default(),
+            Persistence(params.noise_persistence),
+            FractalLayers {
+                layer: Octave::<MixCellGradients<OrthoGrid, Smoothstep, QuickGradients>>::default(),
+                lacunarity: params.noise_lacunarity,
+                amount: params.noise_layers,
+            },
+        ));
+        noise.set_seed(seed);
+        noise.set_frequency(params.granularity as f32 / params.chunk_width);
+
+        TerrainGenerator {
+            noise,
+            params,
+            seed,
+        }
+    }
+
+    pub(crate) fn params(&self) -> &TerrainParams {
+        &self.params
+    }
+
+    /// Unflattened surface height at terrain coordinate `x`.
+    pub(crate) fn sample_height(&self, x: f32) -> f32 {
+        self.noise.sample_for::<f32>(Vec2::new(x, 0.0)) * self.params.height_amplitude
+            + self.params.base_height
+    }
+
+    /// Index of the chunk starting at terrain coordinate `x_origin`, negative
+    /// left of the origin. Rounded, so an origin a hair off the chunk grid still
+    /// maps to its chunk.
+    pub(crate) fn chunk_index(&self, x_origin: f32) -> i64 {
+        (x_origin / self.params.chunk_width).round() as i64
+    }
+
+    /// Heights and pad site of the chunk starting at terrain coordinate `x_origin`.
+    ///
+    /// Pads never reach the chunk edges, so the first and last heights are the
+    /// raw noise shared with the neighboring chunks.
+    pub(crate) fn generate_chunk(&self, x_origin: f32) -> ChunkData {
+        let params = &self.params;
+
+        let mut heights: Vec<f32> = (0..=params.chunk_width as i32)
+            .step_by(params.granularity as usize)
+            .map(|x| self.sample_height(x_origin + x as f32))
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(chunk_seed(self.seed, self.chunk_index(x_origin)));
+
+        let pad = if rng.random_bool(params.pad_chance) {
+            let pad_type_index = rng.random_range(0..params.pad_widths.len());
+            let base = rng.random_bool(params.base_pad_chance);
+
+            flatten_pad(
+                &mut heights,
+                params.pad_widths[pad_type_index],
+                params.granularity,
+                params.pad_flatness_tolerance,
+                params.pad_edge_margin,
+            )
+            .map(|(center, samples)| PadSite {
+                center,
+                width: params.pad_widths[pad_type_index],
+                multiplier: params.pad_multipliers[pad_type_index],
+                base,
+                samples,
+                hazard: None,
+            })
+        } else {
+            None
+        };
+
+        // Rolled after the pad, so adding surfaces left pad layouts unchanged
+        let roll = rng.random::<f64>();
+        let surface = if roll < params.ice_chance {
+            Surface::Ice
+        } else if roll < params.ice_chance + params.soft_regolith_chance {
+            Surface::SoftRegolith
+        } else {
+            Surface::Regolith
+        };
+
+        // Rolled after the surface, so adding hazards left the rest unchanged
+        let pad = pad.map(|pad| {
+            if pad.base || !rng.random_bool(params.hazard_pad_chance) {
+                return pad;
+            }
+
+            let hazard = if pad.width >= params.boulder_pad_min_width && rng.random_bool(0.5) {
+                PadHazard::Boulder {
+                    right: rng.random_bool(0.5),
+                    fraction: params.boulder_pad_fraction,
+                }
+            } else {
+                let slope = if rng.random_bool(0.5) {
+                    params.sloped_pad_slope
+                } else {
+                    -params.sloped_pad_slope
+                };
+                tilt_pad(&mut heights, pad, slope, params.granularity);
+                PadHazard::Sloped(slope)
+            };
+
+            PadSite {
+                multiplier: pad.multiplier * params.hazard_pad_multiplier_factor,
+                hazard: Some(hazard),
+                ..pad
+            }
+        });
+
+        ChunkData {
+            heights,
+            pad,
+            surface,
+        }
+    }
+}
+
+/// Seed for the randomness local to one chunk, mixing the run seed with the chunk
+/// index so pads depend only on both, whatever order chunks are generated in.
+fn chunk_seed(run_seed: u32, chunk_index: i64) -> u64 {
+    splitmix64(splitmix64(run_seed as u64) ^ chunk_index as u64)
+}
+
+/// SplitMix64 finalizer, so that close inputs give unrelated outputs.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Turns the flat stretch of `pad` into a ramp rising by `slope` per unit to the
+/// right, pivoting around the pad center so its height there stays the same.
+fn tilt_pad(heights: &mut [f32], pad: PadSite, slope: f32, granularity: u32) {
+    let (first, last) = pad.samples;
+    for (i, height) in heights.iter_mut().enumerate().take(last + 1).skip(first) {
+        let x = (i * granularity as usize) as f32;
+        *height = pad.center.y + slope * (x - pad.center.x);
+    }
+}
+
+/// Flattens the first stretch of `heights` whose ends are within `tolerance` of
+/// each other and at least `edge_margin` units away from both chunk edges,
+/// returning the pad surface center and the indices of its ends.
+fn flatten_pad(
+    heights: &mut [f32],
+    width: u32,
+    granularity: u32,
+    tolerance: f32,
+    edge_margin: u32,
+) -> Option<(Vec2, (usize, usize))> {
+    let window = (width / granularity) as usize;
+    let margin = (edge_margin / granularity).max(1) as usize;
+
+    for x_0 in margin..heights.len().saturating_sub(window + margin) {
+        let x_1 = x_0 + window;
+        if (heights[x_0] - heights[x_1]).abs() <= tolerance {
+            let pad_height = (heights[x_0] + heights[x_1]) / 2.0;
+            for height in &mut heights[x_0..=x_1] {
+                *height = pad_height;
+            }
+            let pad_x = (x_0 + x_1) as f32 * granularity as f32 / 2.0;
+            return Some((Vec2::new(pad_x, pad_height), (x_0, x_1)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u32; 5] = [0, 1, 42, 0xDEAD_BEEF, u32::MAX];
+
+    fn assert_same_chunk(a: &ChunkData, b: &ChunkData) {
+        assert_eq!(a.heights, b.heights);
+        assert_eq!(a.surface, b.surface);
+        match (a.pad, b.pad) {
+            (Some(a), Some(b)) => {
+                assert_eq!(a.center, b.center);
+                assert_eq!(a.width, b.width);
+                assert_eq!(a.multiplier, b.multiplier);
+                assert_eq!(a.base, b.base);
+                assert_eq!(a.samples, b.samples);
+                assert_eq!(a.hazard, b.hazard);
+            }
+            (None, None) => {}
+            (a, b) => panic!("pad mismatch: {a:?} vs {b:?}"),
+        }
+    }
+
+    #[test]
+    fn chunk_borders_are_continuous() {
+        for seed in SEEDS {
+            let generator = TerrainGenerator::new(seed, TerrainParams::default());
+            let chunk_width = generator.params().chunk_width;
+
+            for index in -20..20 {
+                let chunk = generator.generate_chunk(index as f32 * chunk_width);
+                let next = generator.generate_chunk((index + 1) as f32 * chunk_width);
+
+                assert_eq!(
+                    chunk.heights.last(),
+                    next.heights.first(),
+                    "seed {seed}, chunks {index} and {}",
+                    index + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn flattened_pads_are_level() {
+        let mut pads_checked = 0;
+
+        for seed in SEEDS {
+            let generator = TerrainGenerator::new(seed, TerrainParams::default());
+            let chunk_width = generator.params().chunk_width;
+
+            for index in -20..20 {
+                let chunk = generator.generate_chunk(index as f32 * chunk_width);
+                let Some(pad) = chunk.pad else {
+                    continue;
+                };
+                // Sloped pads are tilted on purpose after flattening
+                if matches!(pad.hazard, Some(PadHazard::Sloped(_))) {
+                    continue;
+                }
+
+                let (first, last) = pad.samples;
+                for height in &chunk.heights[first..=last] {
+                    assert_eq!(*height, pad.center.y, "seed {seed}, chunk {index}");
+                }
+                pads_checked += 1;
+            }
+        }
+
+        assert!(pads_checked > 0);
+    }
+
+    #[test]
+    fn flatten_pad_levels_the_whole_window() {
+        let mut heights: Vec<f32> = (0..100).map(|i| (i as f32 * 0.3).sin() * 3.0).collect();
+
+        let (center, (first, last)) = flatten_pad(&mut heights, 16, 2, 4.0, 8).unwrap();
+
+        assert_eq!(last - first, 8);
+        assert!(first >= 4 && last < heights.len() - 4);
+        for height in &heights[first..=last] {
+            assert_eq!(*height, center.y);
+        }
+    }
+
+    #[test]
+    fn generation_is_deterministic() {
+        for seed in SEEDS {
+            let params = TerrainParams::default();
+            let chunk_width = params.chunk_width;
+            let first = TerrainGenerator::new(seed, params.clone());
+            let second = TerrainGenerator::new(seed, params);
+
+            // Opposite generation orders, as chunks stream in from either side
+            let forward: Vec<ChunkData> = (-10..10)
+                .map(|index| first.generate_chunk(index as f32 * chunk_width))
+                .collect();
+            let backward: Vec<ChunkData> = (-10..10)
+                .rev()
+                .map(|index| second.generate_chunk(index as f32 * chunk_width))
+                .collect();
+
+            for (a, b) in forward.iter().zip(backward.iter().rev()) {
+                assert_same_chunk(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_index_of_negative_origins() {
+        let generator = TerrainGenerator::new(0, TerrainParams::default());
+        let chunk_width = generator.params().chunk_width;
+
+        assert_eq!(generator.chunk_index(0.0), 0);
+        assert_eq!(generator.chunk_index(-chunk_width), -1);
+        assert_eq!(generator.chunk_index(-2.0 * chunk_width), -2);
+        assert_eq!(generator.chunk_index(-chunk_width - 0.01), -1);
+        assert_eq!(generator.chunk_index(-chunk_width + 0.01), -1);
+        // Halfway goes down, as flooring would, not towards zero
+        assert_eq!(generator.chunk_index(-0.5 * chunk_width), -1);
+        assert_eq!(generator.chunk_index(-1.5 * chunk_width), -2);
+    }
+
+    #[test]
+    fn chunk_seeds_are_distinct_and_stable() {
+        use std::collections::HashSet;
+
+        for seed in SEEDS {
+            let seeds: HashSet<u64> = (-100..100).map(|index| chunk_seed(seed, index)).collect();
+            assert_eq!(seeds.len(), 200, "seed {seed}");
+
+            for index in [-1, -2, -100] {
+                let mut rng = StdRng::seed_from_u64(chunk_seed(seed, index));
+                let mut again = StdRng::seed_from_u64(chunk_seed(seed, index));
+                let mut mirrored = StdRng::seed_from_u64(chunk_seed(seed, -index));
+                let roll = rng.random::<u64>();
+                assert_eq!(roll, again.random::<u64>());
+                assert_ne!(roll, mirrored.random::<u64>());
+            }
+        }
+    }
+
+    #[test]
+    fn clear_stretch_runs_from_the_free_edge_to_the_boulder() {
+        let width = 64.0;
+        for right in [false, true] {
+            let hazard = PadHazard::Boulder {
+                right,
+                fraction: 0.25,
+            };
+            let center = hazard.clear_offset(width);
+            let half_clear = hazard.clear_width(width) / 2.0;
+            let (left_end, right_end) = (center - half_clear, center + half_clear);
+
+            if right {
+                assert_eq!((left_end, right_end), (-32.0, 16.0));
+            } else {
+                assert_eq!((left_end, right_end), (-16.0, 32.0));
+            }
+        }
+
+        let sloped = PadHazard::Sloped(0.1);
+        assert_eq!(sloped.clear_offset(width), 0.0);
+        assert_eq!(sloped.clear_width(width), width);
+    }
+}
@@ -0,0 +1,63 @@
+//! Semantic text styles, so restyling the game's text happens here instead of
+//! at every spawn site.
+
+use crate::MainFont;
+
+use bevy::prelude::*;
+
+/// What a piece of text is for, which decides how it looks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextStyle {
+    /// Game title on the main menu.
+    TitleLarge,
+    /// Screen titles and end screen results.
+    Title,
+    /// Menu buttons.
+    Button,
+    /// Buttons in longer lists, like the settings.
+    ButtonSmall,
+    /// Modal prompts over a paused run.
+    Dialog,
+    /// Panels listing choices during a run.
+    Panel,
+    /// Longer text on menus, like the session recap.
+    Body,
+    /// Warnings across the top of the HUD.
+    HudWarning,
+    /// Status line across the bottom of the HUD.
+    HudStatus,
+    /// Readouts in the HUD corner, and help lines.
+    HudValue,
+    /// Small labels placed in the world, like pad multipliers.
+    HudLabel,
+    /// Secondary notices on menus.
+    Notice,
+}
+
+impl TextStyle {
+    fn font_size(self) -> f32 {
+        match self {
+            TextStyle::TitleLarge => 67.0,
+            TextStyle::Title => 48.0,
+            TextStyle::Button => 33.0,
+            TextStyle::Dialog => 32.0,
+            TextStyle::ButtonSmall => 28.0,
+            TextStyle::Panel | TextStyle::Body | TextStyle::HudWarning => 24.0,
+            TextStyle::HudStatus => 20.0,
+            TextStyle::Notice => 18.0,
+            TextStyle::HudValue => 16.0,
+            TextStyle::HudLabel => 12.0,
+        }
+    }
+}
+
+impl MainFont {
+    /// Font settings for text in `style`.
+    pub fn text_font(&self, style: TextStyle) -> TextFont {
+        TextFont {
+            font_size: style.font_size(),
+            font: self.0.clone(),
+            ..default()
+        }
+    }
+}